@@ -1,4 +1,4 @@
-use crate::domain::{DetectionService, PersonalInformationDetector, FileInfo, PersonalInformation, ScanResult};
+use crate::domain::{DetectionService, PersonalInformationDetector, ExtractedText, FileInfo, PersonalInformation, ScanResult};
 use std::error::Error as StdError;
 use std::sync::Arc;
 use async_trait::async_trait;
@@ -21,11 +21,24 @@ impl DetectionService for DetectionServiceImpl {
     }
     
     async fn detect_in_file(&self, file_info: &FileInfo) -> Result<ScanResult, Box<dyn StdError + Send + Sync>> {
-        let personal_information = self.detect_personal_information(&file_info.content).await?;
+        let report = self.detector.detect_with_report(&file_info.content).await?;
+        
+        // 抽出器が位置情報を返した文書では、検出結果にシート名・セル・ページなどの位置を付ける
+        let personal_information = report.personal_information.into_iter()
+            .map(|mut info| {
+                if let Some(location) = ExtractedText::location_at(&file_info.locations, info.line, info.start) {
+                    info.location = Some(location.label.clone());
+                    info.page = location.page;
+                }
+                info
+            })
+            .collect();
         
         Ok(ScanResult {
             file: file_info.path.clone(),
             personal_information,
+            incomplete_detectors: report.incomplete_detectors,
+            cached: false,
         })
     }
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 pub mod scan;
 pub mod detect;
 pub mod format;
+pub mod state;
 
 pub use scan::ScanUseCase;
 pub use detect::DetectionServiceImpl;
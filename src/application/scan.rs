@@ -1,8 +1,10 @@
+use crate::application::state::{FileFingerprint, ScanState};
 use crate::domain::{DetectionService, ScanResult, ScanService};
 use crate::utils::progress::ProgressBar;
 use futures::future;
 use std::error::Error as StdError;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tokio::sync::Semaphore; // ProgressBarのuse宣言を追加
 
@@ -11,6 +13,8 @@ pub struct ScanUseCase {
     scan_service: Arc<dyn ScanService + Send + Sync>,
     detection_service: Arc<dyn DetectionService + Send + Sync>,
     max_concurrency: usize,
+    // 状態ファイルに記録するスキャンの設定の指紋
+    settings_fingerprint: String,
 }
 
 impl ScanUseCase {
@@ -23,20 +27,47 @@ impl ScanUseCase {
             scan_service,
             detection_service,
             max_concurrency: max_concurrency.unwrap_or(4), // デフォルトの並行処理数
+            settings_fingerprint: String::new(),
         }
     }
 
+    /// 検出結果に影響する設定の指紋を設定する（状態ファイルの指紋と異なれば前回の結果を再利用しない）
+    pub fn set_settings_fingerprint(&mut self, fingerprint: String) {
+        self.settings_fingerprint = fingerprint;
+    }
+
     /// ディレクトリ内のファイルをスキャンし、個人情報を検出する
+    ///
+    /// `state_path` を指定すると、前回からサイズ・更新時刻・内容が変わっていないファイルは
+    /// 状態ファイルに保存された前回の結果を `cached` として再利用し、終了時に状態ファイルを更新する。
+    /// 状態ファイルとその一時ファイルはスキャン対象から除く。
     pub async fn scan_directory(
         &self,
         path: &str,
         recursive: bool,
+        state_path: Option<&str>,
     ) -> Result<Vec<ScanResult>, Box<dyn StdError + Send + Sync>> {
         // パスをPathオブジェクトに変換
         let path = Path::new(path);
+        
+        // 前回の状態を読み込む
+        let previous_state = match state_path {
+            Some(state_path) => ScanState::load(Path::new(state_path), &self.settings_fingerprint)?,
+            None => ScanState::new(),
+        };
+        let previous_state = &previous_state;
+        let next_state = Arc::new(Mutex::new(ScanState::with_settings(&self.settings_fingerprint)));
 
-        // ファイルリストを取得
-        let file_paths = self.scan_service.scan_path(path, recursive)?;
+        // ファイルリストを取得（状態ファイルは前回の検出値を含むので除く）
+        let mut file_paths = self.scan_service.scan_path(path, recursive)?;
+        if let Some(state_path) = state_path.map(Path::new) {
+            let state_files: Vec<PathBuf> = [state_path.to_path_buf(), ScanState::temp_path(state_path)].iter()
+                .filter_map(|path| fs::canonicalize(path).ok())
+                .collect();
+            file_paths.retain(|file_path| {
+                fs::canonicalize(file_path).map_or(true, |file_path| !state_files.contains(&file_path))
+            });
+        }
         println!("スキャンされたファイル数: {}", file_paths.len());
 
         // 並行処理のための準備
@@ -51,6 +82,7 @@ impl ScanUseCase {
             let scan_service = Arc::clone(&self.scan_service);
             let detection_service = Arc::clone(&self.detection_service);
             let results = Arc::clone(&results);
+            let next_state = Arc::clone(&next_state);
             let semaphore = Arc::clone(&semaphore);
             let file_path = file_path.clone();
             let progress_bar_clone_arc = Arc::clone(&progress_bar_arc); // Arc をクローン
@@ -59,23 +91,46 @@ impl ScanUseCase {
                 // セマフォを取得（同時実行数を制限）
                 let _permit = semaphore.acquire().await.unwrap();
 
-                // ファイル処理
-                match scan_service.process_file(&file_path) {
-                    Ok(file_info) => {
-                        match detection_service.detect_in_file(&file_info).await {
-                            Ok(scan_result) => {
-                                // 結果を追加
-                                let mut results = results.lock().unwrap();
-                                results.push(scan_result);
-                            }
-                            Err(e) => {
-                                eprintln!(
-                                    "Error detecting personal information in file {}: {}",
-                                    file_path.display(),
-                                    e
-                                );
+                // 前回から変わっていないファイルは前回の結果を再利用する
+                if let Some(previous) = state_path.and_then(|_| previous_state.unchanged(&file_path)) {
+                    next_state.lock().unwrap().insert(&file_path, previous.fingerprint.clone(), previous.results.clone());
+                    results.lock().unwrap().extend(previous.results.iter().map(|result| ScanResult { cached: true, ..result.clone() }));
+                    progress_bar_clone_arc.update();
+                    return;
+                }
+                
+                // 読み込み前の状態を記録する（スキャン中に変更された場合は次回に再スキャンされる）
+                let fingerprint = state_path.and_then(|_| FileFingerprint::of(&file_path).ok());
+
+                // ファイル処理（アーカイブの場合は中のファイルごとに検出する）
+                match scan_service.process_path(&file_path) {
+                    Ok(file_infos) => {
+                        let mut file_results = Vec::new();
+                        let mut complete = true;
+                        for file_info in &file_infos {
+                            match detection_service.detect_in_file(file_info).await {
+                                Ok(scan_result) => {
+                                    complete &= scan_result.incomplete_detectors.is_empty();
+                                    file_results.push(scan_result);
+                                }
+                                Err(e) => {
+                                    complete = false;
+                                    eprintln!(
+                                        "Error detecting personal information in file {}: {}",
+                                        file_info.path,
+                                        e
+                                    );
+                                }
                             }
                         }
+                        
+                        // 一部の検出器が完了しなかった結果は次回に再スキャンする
+                        if let Some(fingerprint) = fingerprint.filter(|_| complete) {
+                            next_state.lock().unwrap().insert(&file_path, fingerprint, file_results.clone());
+                        }
+                        
+                        // 結果を追加
+                        results.lock().unwrap().extend(file_results);
                         progress_bar_clone_arc.update(); // ファイルを処理するたびに進捗バーを更新
                     }
                     Err(e) => {
@@ -95,22 +150,32 @@ impl ScanUseCase {
             .map_err(|_| "Failed to unwrap Arc".to_string())?
             .into_inner()
             .map_err(|_| "Failed to get inner value from Mutex".to_string())?;
+        
+        // 今回の状態を保存する
+        if let Some(state_path) = state_path {
+            let next_state = next_state.lock().unwrap();
+            next_state.save(Path::new(state_path))?;
+        }
 
         Ok(results)
     }
 
-    /// 単一ファイルをスキャンし、個人情報を検出する
+    /// 単一ファイルをスキャンし、個人情報を検出する（アーカイブの場合は中のファイルごとの結果を返す）
     pub async fn scan_file(
         &self,
         path: &str,
-    ) -> Result<ScanResult, Box<dyn StdError + Send + Sync>> {
+    ) -> Result<Vec<ScanResult>, Box<dyn StdError + Send + Sync>> {
         // パスをPathオブジェクトに変換
         let path = Path::new(path);
 
         // ファイル処理
-        let file_info = self.scan_service.process_file(path)?;
+        let file_infos = self.scan_service.process_path(path)?;
 
         // 個人情報検出
-        self.detection_service.detect_in_file(&file_info).await
+        let mut results = Vec::new();
+        for file_info in &file_infos {
+            results.push(self.detection_service.detect_in_file(file_info).await?);
+        }
+        Ok(results)
     }
 }
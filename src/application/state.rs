@@ -0,0 +1,175 @@
+use crate::domain::ScanResult;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// 状態ファイルの形式が変わったら上げる（異なるバージョンの状態ファイルは読み捨てる）
+const STATE_VERSION: u32 = 3;
+
+/// 検出結果に影響するスキャンの設定（設定ファイル・モデル・プロンプトのバージョン・検出器など）の指紋
+///
+/// 状態ファイルに保存し、今回の設定と異なる場合は前回の結果を再利用しない。
+pub fn settings_fingerprint<S: AsRef<str>>(parts: &[S]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_ref().as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// 差分スキャンで変更を判定するためのファイルの特徴
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FileFingerprint {
+    pub size: u64,
+    pub modified_secs: u64,
+    pub modified_nanos: u32,
+    pub content_hash: String,
+}
+
+impl FileFingerprint {
+    /// ファイルのサイズ・更新時刻・内容のハッシュを求める
+    pub fn of(path: &Path) -> Result<Self, Box<dyn StdError + Send + Sync>> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        Ok(FileFingerprint {
+            size: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+            content_hash: format!("{:x}", Sha256::digest(fs::read(path)?)),
+        })
+    }
+
+    // サイズと更新時刻が同じか（内容のハッシュを求める前の簡易チェック）
+    fn same_metadata(&self, path: &Path) -> bool {
+        let Ok(metadata) = fs::metadata(path) else {
+            return false;
+        };
+        let Some(modified) = metadata.modified().ok().and_then(|time| time.duration_since(UNIX_EPOCH).ok()) else {
+            return false;
+        };
+        metadata.len() == self.size
+            && modified.as_secs() == self.modified_secs
+            && modified.subsec_nanos() == self.modified_nanos
+    }
+}
+
+/// 前回のスキャン時のファイルの特徴と結果（アーカイブの場合は中のファイルごとの結果）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileState {
+    pub fingerprint: FileFingerprint,
+    pub results: Vec<ScanResult>,
+}
+
+/// 差分スキャンの状態ファイル（`--state`）の内容
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanState {
+    version: u32,
+    #[serde(default)]
+    settings: String,
+    files: HashMap<String, FileState>,
+}
+
+impl ScanState {
+    pub fn new() -> Self {
+        Self::with_settings("")
+    }
+
+    /// スキャンの設定の指紋（`settings_fingerprint`）を記録した空の状態
+    pub fn with_settings(settings: &str) -> Self {
+        ScanState {
+            version: STATE_VERSION,
+            settings: settings.to_string(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// 状態ファイルを読み込む（ファイルがない場合や壊れている場合、形式が古い場合、スキャンの設定が変わった場合は空の状態）
+    pub fn load(path: &Path, settings: &str) -> Result<Self, Box<dyn StdError + Send + Sync>> {
+        if !path.exists() {
+            return Ok(Self::with_settings(settings));
+        }
+
+        let state: ScanState = match serde_json::from_str(&fs::read_to_string(path)?) {
+            Ok(state) => state,
+            Err(e) => {
+                eprintln!("Ignoring corrupt state file {}: {}", path.display(), e);
+                return Ok(Self::with_settings(settings));
+            }
+        };
+        if state.version != STATE_VERSION {
+            eprintln!("Ignoring state file {} written by an incompatible version", path.display());
+            return Ok(Self::with_settings(settings));
+        }
+        if state.settings != settings {
+            eprintln!("Ignoring state file {}: the scan settings have changed since the last scan", path.display());
+            return Ok(Self::with_settings(settings));
+        }
+        Ok(state)
+    }
+
+    /// 保存中に使う一時ファイルのパス
+    pub fn temp_path(path: &Path) -> PathBuf {
+        path.with_extension("tmp")
+    }
+
+    /// 状態ファイルに保存する
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let temp_path = Self::temp_path(path);
+        fs::write(&temp_path, serde_json::to_string(self)?)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// ファイルが前回から変わっていなければ、その特徴と前回の結果を返す
+    pub fn unchanged(&self, path: &Path) -> Option<&FileState> {
+        let state = self.files.get(&path.to_string_lossy().to_string())?;
+        if !state.fingerprint.same_metadata(path) {
+            return None;
+        }
+
+        let current = FileFingerprint::of(path).ok()?;
+        (current == state.fingerprint).then_some(state)
+    }
+
+    pub fn insert(&mut self, path: &Path, fingerprint: FileFingerprint, results: Vec<ScanResult>) {
+        self.files.insert(path.to_string_lossy().to_string(), FileState { fingerprint, results });
+    }
+}
+
+impl Default for ScanState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_ignores_corrupt_state_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let source = dir.path().join("a.txt");
+        fs::write(&source, "x").unwrap();
+
+        let mut state = ScanState::with_settings("s1");
+        state.insert(&source, FileFingerprint::of(&source).unwrap(), Vec::new());
+        state.save(&path).unwrap();
+        assert!(ScanState::load(&path, "s1").unwrap().unchanged(&source).is_some());
+
+        // 途中で切れた状態ファイルでもスキャンは止めずに最初からやり直す
+        let content = fs::read_to_string(&path).unwrap();
+        fs::write(&path, &content[..content.len() / 2]).unwrap();
+        let state = ScanState::load(&path, "s1").unwrap();
+        assert!(state.files.is_empty());
+        assert_eq!(state.settings, "s1");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::domain::DetectionReport;
 use crate::domain::models::PersonalInformation;
 use async_trait::async_trait;
 use std::error::Error as StdError;
@@ -8,9 +9,22 @@ pub trait PersonalInformationDetector {
     /// テキスト内の個人情報を検出する
     async fn detect(&self, text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>>;
     
+    /// テキスト内の個人情報を検出し、完了しなかった検出器の情報も合わせて返す
+    async fn detect_with_report(&self, text: &str) -> Result<DetectionReport, Box<dyn StdError + Send + Sync>> {
+        Ok(DetectionReport {
+            personal_information: self.detect(text).await?,
+            incomplete_detectors: Vec::new(),
+        })
+    }
+    
     /// 検出器の名前を返す
     fn name(&self) -> &str;
     
+    /// この検出器が報告しうる検出タイプを返す（`None` はタイプを限定しない検出器）
+    fn supported_types(&self) -> Option<Vec<String>> {
+        None
+    }
+    
     /// この検出器が使用可能かどうかを返す
     fn is_available(&self) -> bool {
         true
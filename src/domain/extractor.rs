@@ -1,11 +1,27 @@
+use crate::domain::ExtractedText;
 use std::error::Error as StdError;
 use std::path::Path;
 
 /// テキスト抽出器のインターフェース
 pub trait TextExtractor: Send + Sync {
+    /// この抽出器が対応する拡張子（小文字）を返す
+    fn extensions(&self) -> &'static [&'static str];
+    
     /// この抽出器がサポートするファイルタイプを返す
-    fn supports(&self, file_type: &str) -> bool;
+    fn supports(&self, file_type: &str) -> bool {
+        self.extensions().contains(&file_type.to_lowercase().as_str())
+    }
     
     /// ファイルからテキストを抽出する
     fn extract(&self, file_path: &Path) -> Result<String, Box<dyn StdError + Send + Sync>>;
+    
+    /// ファイルからテキストと位置情報（シートのセルなど）を抽出する
+    fn extract_with_locations(&self, file_path: &Path) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        Ok(ExtractedText::plain(self.extract(file_path)?))
+    }
+    
+    /// メモリ上のデータ（アーカイブの中のファイルなど）からテキストと位置情報を抽出する
+    fn extract_bytes_with_locations(&self, _data: &[u8]) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        Err("This extractor does not support in-memory extraction".into())
+    }
 }
\ No newline at end of file
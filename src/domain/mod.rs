@@ -4,7 +4,7 @@ pub mod extractor;
 pub mod service;
 
 // Re-export commonly used types
-pub use models::{FileInfo, PersonalInformation, ScanResult};
+pub use models::{DetectionReport, ExtractedText, FileInfo, PersonalInformation, ScanResult, Severity};
 pub use detector::PersonalInformationDetector;
 pub use extractor::TextExtractor;
 pub use service::{ScanService, DetectionService, OutputService};
\ No newline at end of file
@@ -7,16 +7,134 @@ pub struct PersonalInformation {
     pub line: u32,
     pub start: u32,
     pub end: u32,
+    /// 検出器の確信度 (0.0〜1.0)
+    #[serde(default)]
+    pub confidence: f32,
+    /// 検出した検出器の名前
+    #[serde(default)]
+    pub detector: String,
+    #[serde(default)]
+    pub severity: Severity,
+    /// 行番号に意味のない文書での位置（例: スプレッドシートの `Customers!C12`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// ページのある文書（PDF）でのページ番号（1始まり）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// 検出された情報が漏えいした場合の深刻度
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// 検出タイプから既定の深刻度を求める
+    pub fn for_type(type_: &str) -> Self {
+        match type_ {
+            "credit_card" | "my_number" | "private_key" | "jwt" | "slack_webhook" => Severity::Critical,
+            t if t.ends_with("_token") || t.ends_with("_key") => Severity::Critical,
+            "address" | "phone_number" | "high_entropy_string" => Severity::High,
+            "postal_code" | "corporate_number" => Severity::Low,
+            _ => Severity::Medium,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ScanResult {
     pub file: String,
     pub personal_information: Vec<PersonalInformation>,
+    /// タイムアウトやエラーで結果を返せなかった検出器
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub incomplete_detectors: Vec<String>,
+    /// 前回のスキャンから変更がなく、前回の結果を再利用した
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+}
+
+/// 検出器の実行結果
+#[derive(Debug, Default)]
+pub struct DetectionReport {
+    pub personal_information: Vec<PersonalInformation>,
+    /// タイムアウトやエラーで結果を返せなかった検出器
+    pub incomplete_detectors: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct FileInfo {
     pub path: String,
     pub content: String,
-}
\ No newline at end of file
+    /// 抽出したテキストの各部分が元の文書のどこにあるか
+    pub locations: Vec<TextLocation>,
+}
+
+/// 抽出したテキストの (line, start) 以降が元の文書のどこにあるかを表すラベル
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLocation {
+    pub line: u32,
+    pub start: u32,
+    pub label: String,
+    pub page: Option<u32>,
+}
+
+/// 抽出器が返すテキストと位置情報
+///
+/// テキストは `push_labeled` などのメソッドで追加する（現在の行番号と行頭の位置を追加のたびに更新するため）。
+#[derive(Debug, Default)]
+pub struct ExtractedText {
+    pub text: String,
+    pub locations: Vec<TextLocation>,
+    // これまでに追加した改行の数
+    line_breaks: u32,
+    // 現在の行の先頭のバイト位置
+    line_start: usize,
+}
+
+impl ExtractedText {
+    /// 位置情報のないテキスト
+    pub fn plain(text: String) -> Self {
+        let mut extracted = ExtractedText::default();
+        extracted.push_str(&text);
+        extracted
+    }
+
+    /// 現在の位置にラベルを付けてテキストを追加する（テキスト中の改行は空白に置き換える）
+    pub fn push_labeled(&mut self, label: &str, text: &str) {
+        self.push_on_page(None, label, text);
+    }
+
+    /// ページ番号とラベルを付けてテキストを追加する
+    pub fn push_on_page(&mut self, page: Option<u32>, label: &str, text: &str) {
+        let line = self.line_breaks + 1;
+        let start = (self.text.len() - self.line_start) as u32;
+        self.locations.push(TextLocation { line, start, label: label.to_string(), page });
+        self.text.extend(text.chars().map(|c| if c == '\n' || c == '\r' { ' ' } else { c }));
+    }
+
+    /// 現在の行に区切り文字などのテキストを追加する
+    pub fn push_str(&mut self, text: &str) {
+        for (pos, _) in text.match_indices('\n') {
+            self.line_breaks += 1;
+            self.line_start = self.text.len() + pos + 1;
+        }
+        self.text.push_str(text);
+    }
+
+    /// 行を終える
+    pub fn end_line(&mut self) {
+        self.push_str("\n");
+    }
+
+    /// 検出位置 (line, start) を含む部分の位置情報（`locations` は位置の順に並んでいること）
+    pub fn location_at(locations: &[TextLocation], line: u32, start: u32) -> Option<&TextLocation> {
+        let count = locations.partition_point(|location| (location.line, location.start) <= (line, start));
+        count.checked_sub(1).map(|index| &locations[index])
+    }
+}
@@ -10,12 +10,18 @@ pub trait ScanService {
     
     /// ファイルを処理して内容を抽出する
     fn process_file(&self, file_path: &Path) -> Result<FileInfo, Box<dyn StdError + Send + Sync>>;
+    
+    /// ファイルを処理して内容を抽出する（アーカイブの場合は中のファイルごとに返す）
+    fn process_path(&self, file_path: &Path) -> Result<Vec<FileInfo>, Box<dyn StdError + Send + Sync>> {
+        Ok(vec![self.process_file(file_path)?])
+    }
 }
 
 /// 個人情報検出サービスのトレイト
 #[async_trait]
 pub trait DetectionService {
     /// テキスト内の個人情報を検出する
+    #[allow(unused)]
     async fn detect_personal_information(&self, text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>>;
     
     /// ファイルの内容から個人情報を検出する
@@ -0,0 +1,209 @@
+use crate::utils::config::ChunkConfig;
+
+// 長い行を分割した断片の前の断片と重ねる文字数（断片の境界をまたぐ値もどちらかの断片に丸ごと入るように）
+const SEGMENT_OVERLAP_CHARS: usize = 64;
+
+/// LLMに送るテキストの断片
+#[derive(Debug, Clone)]
+pub struct TextChunk {
+    pub text: String,
+    /// 元の文書での先頭行（0始まり）
+    pub first_line: u32,
+    /// 先頭行の途中から始まる場合の元の行内での開始位置
+    pub start_column: u32,
+}
+
+impl TextChunk {
+    /// チャンク内の位置（行は1始まり）を元の文書での位置に変換する
+    pub fn to_document_position(&self, line: u32, start: u32, end: u32) -> (u32, u32, u32) {
+        let line = line.max(1);
+        let column = if line == 1 { self.start_column } else { 0 };
+        (self.first_line + line, start + column, end + column)
+    }
+}
+
+// 行または長い行を分割した断片
+struct Piece<'a> {
+    line: u32,
+    column: u32,
+    text: &'a str,
+    tokens: usize,
+    // 長い行を分割した断片かどうか
+    is_segment: bool,
+}
+
+/// 行数と推定トークン数の上限に従ってテキストをチャンクに分割する
+pub struct TextChunker {
+    config: ChunkConfig,
+}
+
+impl TextChunker {
+    pub fn new(config: ChunkConfig) -> Self {
+        TextChunker { config }
+    }
+
+    pub fn split(&self, text: &str) -> Vec<TextChunk> {
+        let max_lines = self.config.max_lines.max(1);
+        let max_tokens = self.config.max_tokens.max(1);
+        let pieces = self.pieces(text, max_tokens);
+
+        let mut chunks = Vec::new();
+        let mut i = 0;
+        while i < pieces.len() {
+            // 長い行の断片は単独のチャンクにする
+            if pieces[i].is_segment {
+                chunks.push(TextChunk {
+                    text: pieces[i].text.to_string(),
+                    first_line: pieces[i].line,
+                    start_column: pieces[i].column,
+                });
+                i += 1;
+                continue;
+            }
+
+            let mut j = i;
+            let mut tokens = 0;
+            while j < pieces.len()
+                && !pieces[j].is_segment
+                && j - i < max_lines
+                && (j == i || tokens + pieces[j].tokens <= max_tokens)
+            {
+                tokens += pieces[j].tokens;
+                j += 1;
+            }
+
+            chunks.push(TextChunk {
+                text: pieces[i..j].iter().map(|piece| piece.text).collect::<Vec<_>>().join("\n"),
+                first_line: pieces[i].line,
+                start_column: 0,
+            });
+
+            if j >= pieces.len() {
+                break;
+            }
+            // 次のチャンクは重なり分だけ前から始める（長い行の断片の手前では重ねない）
+            i = if pieces[j].is_segment {
+                j
+            } else {
+                j.saturating_sub(self.config.overlap_lines).max(i + 1)
+            };
+        }
+
+        chunks
+    }
+
+    // テキストを行単位の断片に分け、上限を超える行はさらに分割する
+    // 分割した断片は前の断片の末尾と重ねる（重なりは上限の半分のトークン数まで）
+    fn pieces<'a>(&self, text: &'a str, max_tokens: usize) -> Vec<Piece<'a>> {
+        let mut pieces = Vec::new();
+
+        for (line_idx, line) in text.lines().enumerate() {
+            let tokens = estimate_tokens(line);
+            if tokens <= max_tokens {
+                pieces.push(Piece { line: line_idx as u32, column: 0, text: line, tokens, is_segment: false });
+                continue;
+            }
+
+            let mut segment_start = 0;
+            let mut segment_tokens = 0.0;
+            for (pos, c) in line.char_indices() {
+                let cost = char_tokens(c);
+                if segment_tokens + cost > max_tokens as f64 && pos > segment_start {
+                    pieces.push(Piece {
+                        line: line_idx as u32,
+                        column: segment_start as u32,
+                        text: &line[segment_start..pos],
+                        tokens: segment_tokens.ceil() as usize,
+                        is_segment: true,
+                    });
+                    segment_start = overlap_start(&line[segment_start..pos], max_tokens as f64 / 2.0) + segment_start;
+                    segment_tokens = line[segment_start..pos].chars().map(char_tokens).sum();
+                }
+                segment_tokens += cost;
+            }
+            pieces.push(Piece {
+                line: line_idx as u32,
+                column: segment_start as u32,
+                text: &line[segment_start..],
+                tokens: segment_tokens.ceil() as usize,
+                is_segment: true,
+            });
+        }
+
+        pieces
+    }
+}
+
+// 断片の末尾から重ねる部分の開始位置（断片の先頭は含めず、必ず前に進むようにする）
+fn overlap_start(segment: &str, max_overlap_tokens: f64) -> usize {
+    let mut start = segment.len();
+    let mut tokens = 0.0;
+    for (count, (pos, c)) in segment.char_indices().rev().enumerate() {
+        if count >= SEGMENT_OVERLAP_CHARS || pos == 0 || tokens + char_tokens(c) > max_overlap_tokens {
+            break;
+        }
+        tokens += char_tokens(c);
+        start = pos;
+    }
+    start
+}
+
+// 1文字あたりの推定トークン数（ASCIIは約4文字で1トークン、それ以外は1文字1トークン）
+fn char_tokens(c: char) -> f64 {
+    if c.is_ascii() { 0.25 } else { 1.0 }
+}
+
+/// テキストの推定トークン数
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().map(char_tokens).sum::<f64>().ceil() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_with_overlap_and_long_lines() {
+        let chunker = TextChunker::new(ChunkConfig {
+            max_lines: 3,
+            max_tokens: 10,
+            overlap_lines: 1,
+            max_concurrency: 1,
+        });
+        let text = "a\nb\nc\nd\nあいうえおかきくけこさしすせそ\ne";
+        let chunks = chunker.split(text);
+
+        let summary: Vec<(&str, u32, u32)> = chunks.iter()
+            .map(|chunk| (chunk.text.as_str(), chunk.first_line, chunk.start_column))
+            .collect();
+        assert_eq!(summary, vec![
+            ("a\nb\nc", 0, 0),
+            ("c\nd", 2, 0),
+            ("あいうえおかきくけこ", 4, 0),
+            ("かきくけこさしすせそ", 4, 15),
+            ("e", 5, 0),
+        ]);
+
+        assert_eq!(chunks[1].to_document_position(2, 0, 1), (4, 0, 1));
+        assert_eq!(chunks[3].to_document_position(1, 3, 6), (5, 18, 21));
+    }
+
+    #[test]
+    fn test_value_straddling_a_segment_boundary_is_kept_whole() {
+        let chunker = TextChunker::new(ChunkConfig {
+            max_lines: 3,
+            max_tokens: 20,
+            overlap_lines: 0,
+            max_concurrency: 1,
+        });
+        let line = format!("{} taro@example.com {}", "x".repeat(70), "y".repeat(100));
+        let chunks = chunker.split(&line);
+
+        // 80文字ごとの境界をメールアドレスがまたぐ
+        assert!(!line[..80].contains("taro@example.com"));
+        assert!(chunks.len() > 2);
+        let chunk = chunks.iter().find(|chunk| chunk.text.contains("taro@example.com")).unwrap();
+        let start = chunk.text.find("taro@example.com").unwrap() as u32;
+        assert_eq!(chunk.to_document_position(1, start, start + 16), (1, 71, 87));
+    }
+}
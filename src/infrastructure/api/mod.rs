@@ -1,3 +1,10 @@
 pub mod ollama_client;
+pub mod openai_client;
+pub mod chunker;
+pub mod prompt;
+pub mod response_cache;
+pub mod schema;
 
-pub use ollama_client::{ApiClient, OllamaClient};
\ No newline at end of file
+pub use ollama_client::{ApiClient, OllamaClient};
+pub use openai_client::OpenAiCompatibleClient;
+pub use response_cache::ResponseCache;
\ No newline at end of file
@@ -1,3 +1,5 @@
+use crate::infrastructure::api::prompt::{PromptBuilder, RenderedPrompt};
+use crate::infrastructure::api::schema;
 use crate::infrastructure::error::AppError;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -9,10 +11,22 @@ use async_trait::async_trait;
 #[async_trait]
 pub trait ApiClient {
     async fn call(&self, text: &str) -> Result<String, Box<dyn StdError + Send + Sync>>;
+    /// 生成済みのプロンプトを送り、`schema` に沿ったJSON文字列の応答を返す
+    async fn call_with_prompt(&self, prompt: &RenderedPrompt, schema: &serde_json::Value) -> Result<String, Box<dyn StdError + Send + Sync>>;
     #[allow(unused)]
     fn get_url(&self) -> &str;
     #[allow(unused)]
     fn get_model(&self) -> &str;
+    
+    /// `call` で使うプロンプトのバージョン（応答のキャッシュのキーに使う）
+    fn prompt_version(&self) -> String {
+        String::new()
+    }
+    
+    /// サーバーに接続でき、モデルが使える状態かを確認する（`pull_missing` がtrueなら未導入のモデルを取得する）
+    async fn check_model(&self, _pull_missing: bool) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        Ok(())
+    }
 }
 
 // Ollamaリクエスト構造体
@@ -20,7 +34,8 @@ pub trait ApiClient {
 struct OllamaRequest {
     model: String,
     prompt: String,
-    format: Option<String>,
+    format: Option<serde_json::Value>,
+    stream: bool,
 }
 
 // Ollamaレスポンス構造体
@@ -37,8 +52,27 @@ struct OllamaStreamResponse {
     done: bool,
 }
 
+// インストール済みモデル一覧（/api/tags）のレスポンス構造体
+#[derive(Deserialize)]
+struct OllamaTagsResponse {
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+// モデル取得（/api/pull）のリクエスト構造体
+#[derive(Serialize)]
+struct OllamaPullRequest {
+    model: String,
+    stream: bool,
+}
+
 // 定数
 const DEFAULT_TIMEOUT_MS: u64 = 60000; // 60秒
+const HEALTH_CHECK_TIMEOUT_MS: u64 = 5000;
 const MAX_RETRIES: u32 = 3;
 const RETRY_DELAY_MS: u64 = 1000;
 
@@ -47,6 +81,7 @@ pub struct OllamaClient {
     api_url: String,
     model: String,
     timeout_ms: u64,
+    prompt_builder: PromptBuilder,
     client: Client,
 }
 
@@ -56,35 +91,81 @@ impl OllamaClient {
             api_url: api_url.to_string(),
             model: model.to_string(),
             timeout_ms: timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS),
+            prompt_builder: PromptBuilder::new(),
             client: Client::new(),
         }
     }
     
-    // 個人情報検出用プロンプトを生成
-    fn create_personal_info_prompt(&self, text: &str) -> String {
-        format!(
-            "以下のテキストを解析し、含まれている個人情報（氏名、メールアドレス、電話番号、住所、クレジットカード番号など）を検出してください。\
-            検出結果はJSON形式で返してください。各項目について、種類（type）、値（value）、行番号（line）、開始位置（start）、終了位置（end）を含めてください。\
-            \n\nテキスト:\n{}",
-            text
-        )
+    /// プロンプトテンプレートを変更する
+    pub fn set_prompt_builder(&mut self, prompt_builder: PromptBuilder) {
+        self.prompt_builder = prompt_builder;
+    }
+    
+    // `/api/generate` などのエンドポイントからサーバーのベースURLを求める
+    fn base_url(&self) -> &str {
+        match self.api_url.find("/api/") {
+            Some(pos) => &self.api_url[..pos],
+            None => self.api_url.trim_end_matches('/'),
+        }
+    }
+    
+    // タグ指定のないモデル名は `:latest` として扱う
+    fn is_same_model(&self, installed: &str) -> bool {
+        installed == self.model || (!self.model.contains(':') && installed == format!("{}:latest", self.model))
+    }
+    
+    // インストール済みのモデル名の一覧を取得する
+    async fn list_models(&self) -> Result<Vec<String>, Box<dyn StdError + Send + Sync>> {
+        let url = format!("{}/api/tags", self.base_url());
+        let response = self.client.get(&url)
+            .timeout(Duration::from_millis(HEALTH_CHECK_TIMEOUT_MS))
+            .send()
+            .await
+            .map_err(|e| AppError::ApiError(format!("Ollama server is not reachable at {}: {}", self.base_url(), e)))?;
+        if !response.status().is_success() {
+            return Err(AppError::ApiError(format!("{} returned error status: {}", url, response.status())).into());
+        }
+        
+        let tags = response.json::<OllamaTagsResponse>().await
+            .map_err(|e| AppError::ApiError(format!("Failed to parse {} response: {}", url, e)))?;
+        Ok(tags.models.into_iter().map(|model| model.name).collect())
+    }
+    
+    // モデルを取得する（完了まで待つ）
+    async fn pull_model(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        eprintln!("Pulling model '{}' from Ollama...", self.model);
+        let response = self.client.post(format!("{}/api/pull", self.base_url()))
+            .json(&OllamaPullRequest { model: self.model.clone(), stream: false })
+            .send()
+            .await
+            .map_err(|e| AppError::ApiError(format!("Failed to pull model '{}': {}", self.model, e)))?;
+        if !response.status().is_success() {
+            return Err(AppError::ApiError(format!("Failed to pull model '{}': status {}", self.model, response.status())).into());
+        }
+        
+        eprintln!("Model '{}' is ready", self.model);
+        Ok(())
     }
 }
 
 #[async_trait]
 impl ApiClient for OllamaClient {
     async fn call(&self, text: &str) -> Result<String, Box<dyn StdError + Send + Sync>> {
+        // プロンプトを生成
+        let prompt = self.prompt_builder.render(text);
+        self.call_with_prompt(&prompt, &schema::personal_info_schema()).await
+    }
+    
+    async fn call_with_prompt(&self, prompt: &RenderedPrompt, schema: &serde_json::Value) -> Result<String, Box<dyn StdError + Send + Sync>> {
         let mut tries = 0;
         let mut last_error = None;
         
-        // プロンプトを生成
-        let prompt = self.create_personal_info_prompt(text);
-        
         // リクエストボディを作成
         let request_body = OllamaRequest {
             model: self.model.clone(),
-            prompt,
-            format: Some("json".to_string()),
+            prompt: prompt.to_single_prompt(),
+            format: Some(schema.clone()),
+            stream: false,
         };
         
         while tries < MAX_RETRIES {
@@ -102,7 +183,9 @@ impl ApiClient for OllamaClient {
                             match response.json::<OllamaResponse>().await {
                                 Ok(result) => {
                                     let elapsed = start_time.elapsed();
-                                    println!("API request completed in {:.2?}", elapsed);
+                                    eprintln!("API request completed in {:.2?}", elapsed);
+                                    // スキーマ違反はモデルの出力の問題なので再試行せずに報告する
+                                    schema::validate_response(&result.response, schema)?;
                                     return Ok(result.response);
                                 }
                                 Err(e) => {
@@ -120,7 +203,7 @@ impl ApiClient for OllamaClient {
             
             tries += 1;
             if tries < MAX_RETRIES {
-                println!("Retrying API call ({}/{}), waiting for {}ms...", tries, MAX_RETRIES, RETRY_DELAY_MS);
+                eprintln!("Retrying API call ({}/{}), waiting for {}ms...", tries, MAX_RETRIES, RETRY_DELAY_MS);
                 tokio::time::sleep(Duration::from_millis(RETRY_DELAY_MS)).await;
             }
         }
@@ -135,4 +218,86 @@ impl ApiClient for OllamaClient {
     fn get_model(&self) -> &str {
         &self.model
     }
-}
\ No newline at end of file
+    
+    fn prompt_version(&self) -> String {
+        self.prompt_builder.version()
+    }
+    
+    async fn check_model(&self, pull_missing: bool) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let models = self.list_models().await?;
+        if models.iter().any(|name| self.is_same_model(name)) {
+            return Ok(());
+        }
+        
+        if pull_missing {
+            self.pull_model().await
+        } else {
+            Err(AppError::ApiError(format!(
+                "model '{}' is not installed (run `ollama pull {}` or pass --pull-model)",
+                self.model, self.model
+            )).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_call_sends_schema_and_rejects_violations() {
+        let mut server = mockito::Server::new_async().await;
+        let schema_body = mockito::Matcher::PartialJson(serde_json::json!({
+            "model": "llama3.1",
+            "format": schema::personal_info_schema(),
+        }));
+        let valid = server.mock("POST", "/api/generate")
+            .match_body(schema_body.clone())
+            .with_body(r#"{"response":"{\"personal_information\":[]}"}"#)
+            .create_async()
+            .await;
+
+        let client = OllamaClient::new(&format!("{}/api/generate", server.url()), "llama3.1", None);
+        assert_eq!(client.call("text").await.unwrap(), r#"{"personal_information":[]}"#);
+        valid.assert_async().await;
+        valid.remove_async().await;
+
+        let invalid = server.mock("POST", "/api/generate")
+            .match_body(schema_body)
+            .with_body(r#"{"response":"{\"personal_information\":[{\"type\":\"email\"}]}"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let err = client.call("text").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<AppError>(), Some(AppError::SchemaError(_))));
+        invalid.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_check_model_reports_missing_model_and_pulls_on_request() {
+        let mut server = mockito::Server::new_async().await;
+        let tags = server.mock("GET", "/api/tags")
+            .with_body(r#"{"models":[{"name":"deepseek-coder:latest"}]}"#)
+            .expect(3)
+            .create_async()
+            .await;
+        let pull = server.mock("POST", "/api/pull")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "model": "llama3.1" })))
+            .with_body(r#"{"status":"success"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let api_url = format!("{}/api/generate", server.url());
+        assert!(OllamaClient::new(&api_url, "deepseek-coder", None).check_model(false).await.is_ok());
+
+        let missing = OllamaClient::new(&api_url, "llama3.1", None);
+        let err = missing.check_model(false).await.unwrap_err();
+        assert!(err.to_string().contains("not installed"));
+        assert!(missing.check_model(true).await.is_ok());
+
+        tags.assert_async().await;
+        pull.assert_async().await;
+    }
+}
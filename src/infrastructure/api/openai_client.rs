@@ -0,0 +1,228 @@
+use crate::infrastructure::api::ApiClient;
+use crate::infrastructure::api::prompt::{PromptBuilder, RenderedPrompt};
+use crate::infrastructure::api::schema;
+use crate::infrastructure::error::AppError;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+
+// チャットメッセージ
+#[derive(Serialize, Deserialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+// 応答形式の指定（JSONモード）
+#[derive(Serialize)]
+struct ResponseFormat {
+    #[serde(rename = "type")]
+    type_: String,
+}
+
+// chat completionsリクエスト構造体
+#[derive(Serialize)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    response_format: ResponseFormat,
+    temperature: f32,
+}
+
+// chat completionsレスポンス構造体
+#[derive(Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+// 定数
+const DEFAULT_TIMEOUT_MS: u64 = 60000; // 60秒
+const MAX_RETRIES: u32 = 3;
+const RETRY_DELAY_MS: u64 = 1000;
+
+/// OpenAI互換の `/v1/chat/completions` API（llama.cpp server, vLLM, LM Studioなど）のクライアント
+pub struct OpenAiCompatibleClient {
+    api_url: String,
+    model: String,
+    api_key: Option<String>,
+    timeout_ms: u64,
+    prompt_builder: PromptBuilder,
+    client: Client,
+}
+
+impl OpenAiCompatibleClient {
+    pub fn new(api_url: &str, model: &str, api_key: Option<String>, timeout_ms: Option<u64>) -> Self {
+        OpenAiCompatibleClient {
+            api_url: api_url.to_string(),
+            model: model.to_string(),
+            api_key,
+            timeout_ms: timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS),
+            prompt_builder: PromptBuilder::new(),
+            client: Client::new(),
+        }
+    }
+    
+    /// プロンプトテンプレートを変更する
+    pub fn set_prompt_builder(&mut self, prompt_builder: PromptBuilder) {
+        self.prompt_builder = prompt_builder;
+    }
+    
+    // システムメッセージに指示文、ユーザーメッセージに本文を入れたリクエストを作成
+    fn create_request(&self, prompt: &RenderedPrompt) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: self.model.clone(),
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: prompt.instructions.clone(),
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: prompt.body.clone(),
+                },
+            ],
+            response_format: ResponseFormat {
+                type_: "json_object".to_string(),
+            },
+            temperature: 0.0,
+        }
+    }
+}
+
+#[async_trait]
+impl ApiClient for OpenAiCompatibleClient {
+    async fn call(&self, text: &str) -> Result<String, Box<dyn StdError + Send + Sync>> {
+        let prompt = self.prompt_builder.render(text);
+        self.call_with_prompt(&prompt, &schema::personal_info_schema()).await
+    }
+    
+    // JSONモードではスキーマを指定できないため、応答を受け取った後にスキーマで検証する
+    async fn call_with_prompt(&self, prompt: &RenderedPrompt, schema: &serde_json::Value) -> Result<String, Box<dyn StdError + Send + Sync>> {
+        let mut tries = 0;
+        let mut last_error = None;
+        
+        // リクエストボディを作成
+        let request_body = self.create_request(prompt);
+        
+        while tries < MAX_RETRIES {
+            let start_time = Instant::now();
+            
+            let mut request = self.client.post(&self.api_url)
+                .json(&request_body)
+                .timeout(Duration::from_millis(self.timeout_ms));
+            if let Some(api_key) = &self.api_key {
+                request = request.bearer_auth(api_key);
+            }
+            
+            // APIリクエスト
+            match request.send().await {
+                Ok(response) => {
+                    if response.status().is_success() {
+                        // レスポンスをJSONとしてパースし、最初の選択肢の本文を返す
+                        match response.json::<ChatCompletionResponse>().await {
+                            Ok(result) => match result.choices.into_iter().next() {
+                                Some(choice) => {
+                                    let elapsed = start_time.elapsed();
+                                    eprintln!("API request completed in {:.2?}", elapsed);
+                                    // スキーマ違反はモデルの出力の問題なので再試行せずに報告する
+                                    schema::validate_response(&choice.message.content, schema)?;
+                                    return Ok(choice.message.content);
+                                }
+                                None => {
+                                    last_error = Some("API response contained no choices".to_string());
+                                }
+                            },
+                            Err(e) => {
+                                last_error = Some(format!("Failed to parse API response: {}", e));
+                            }
+                        }
+                    } else {
+                        last_error = Some(format!("API returned error status: {}", response.status()));
+                    }
+                }
+                Err(e) => {
+                    last_error = Some(format!("API request failed: {}", e));
+                }
+            }
+            
+            tries += 1;
+            if tries < MAX_RETRIES {
+                eprintln!("Retrying API call ({}/{}), waiting for {}ms...", tries, MAX_RETRIES, RETRY_DELAY_MS);
+                tokio::time::sleep(Duration::from_millis(RETRY_DELAY_MS)).await;
+            }
+        }
+        
+        Err(AppError::ApiError(last_error.unwrap_or_else(|| "Unknown API error".to_string())).into())
+    }
+    
+    fn get_url(&self) -> &str {
+        &self.api_url
+    }
+    
+    fn get_model(&self) -> &str {
+        &self.model
+    }
+    
+    fn prompt_version(&self) -> String {
+        self.prompt_builder.version()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_call_sends_messages_and_bearer_token() {
+        let prompt = PromptBuilder::new().render("test@example.com");
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/v1/chat/completions")
+            .match_header("authorization", "Bearer secret-token")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "model": "local-model",
+                "response_format": { "type": "json_object" },
+                "messages": [
+                    { "role": "system", "content": prompt.instructions },
+                    { "role": "user", "content": prompt.body }
+                ]
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"choices":[{"message":{"role":"assistant","content":"{\"personal_information\":[]}"}}]}"#)
+            .create_async()
+            .await;
+
+        let client = OpenAiCompatibleClient::new(
+            &format!("{}/v1/chat/completions", server.url()),
+            "local-model",
+            Some("secret-token".to_string()),
+            None,
+        );
+        let response = client.call("test@example.com").await.unwrap();
+
+        assert_eq!(response, r#"{"personal_information":[]}"#);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_call_rejects_response_outside_schema() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/v1/chat/completions")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"choices":[{"message":{"role":"assistant","content":"Here you go: {\"personal_information\":[]}"}}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = OpenAiCompatibleClient::new(&format!("{}/v1/chat/completions", server.url()), "local-model", None, None);
+        let err = client.call("test@example.com").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<AppError>(), Some(AppError::SchemaError(_))));
+        mock.assert_async().await;
+    }
+}
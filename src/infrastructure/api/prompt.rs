@@ -0,0 +1,233 @@
+use crate::infrastructure::error::AppError;
+use crate::utils::config::{PromptConfig, PromptExample};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+
+// 組み込みのプロンプトテンプレート
+const JA_TEMPLATE: &str = include_str!("prompts/ja.txt");
+const EN_TEMPLATE: &str = include_str!("prompts/en.txt");
+
+// プロンプトの組み立て方を変更したら上げる（キャッシュのキーに含まれる）
+const PROMPT_FORMAT_VERSION: &str = "1";
+
+// テンプレートのプレースホルダー
+const TEXT_PLACEHOLDER: &str = "{text}";
+const TYPES_PLACEHOLDER: &str = "{types}";
+const EXAMPLES_PLACEHOLDER: &str = "{examples}";
+
+// 検出候補の確認（verifyモード）の指示文
+const JA_VERIFY_INSTRUCTIONS: &str = "以下は自動検出された個人情報の候補です。各候補の context では候補の値を [[ ]] で囲んでいます。\
+    前後の文脈から、候補が本当にその種類の個人情報であるかを判断してください（例: 電話番号に見える注文番号は誤検出です）。\
+    結果は {\"verdicts\": [{\"id\": 候補のid, \"keep\": 個人情報ならtrue, \"reason\": 判断の理由}]} 形式のJSONで、すべての候補について返してください。";
+const EN_VERIFY_INSTRUCTIONS: &str = "Below are candidates that were automatically detected as personal information. In each candidate's context the value is wrapped in [[ ]]. \
+    Decide from the surrounding context whether each candidate really is personal information of that type (for example, an order number that looks like a phone number is a false positive). \
+    Answer for every candidate with JSON in the form {\"verdicts\": [{\"id\": candidate id, \"keep\": true if it is personal information, \"reason\": why}]}.";
+
+// 日本語の文字がこの割合以上含まれていれば日本語の文書とみなす
+const JAPANESE_RATIO_THRESHOLD: f32 = 0.2;
+
+/// プロンプトテンプレートの言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Japanese,
+    English,
+}
+
+impl Language {
+    // 設定ファイルで使う言語コードから変換する
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "ja" => Some(Language::Japanese),
+            "en" => Some(Language::English),
+            _ => None,
+        }
+    }
+
+    /// かな・漢字と英字の割合から文書の言語を判定する（判定できない場合は日本語）
+    pub fn detect(text: &str) -> Self {
+        let (japanese, latin) = text.chars().fold((0usize, 0usize), |(japanese, latin), c| {
+            if is_japanese_char(c) {
+                (japanese + 1, latin)
+            } else if c.is_ascii_alphabetic() {
+                (japanese, latin + 1)
+            } else {
+                (japanese, latin)
+            }
+        });
+
+        if latin > 0 && (japanese as f32) < (japanese + latin) as f32 * JAPANESE_RATIO_THRESHOLD {
+            Language::English
+        } else {
+            Language::Japanese
+        }
+    }
+}
+
+fn is_japanese_char(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // ひらがな・カタカナ
+        | '\u{4E00}'..='\u{9FFF}' // CJK統合漢字
+        | '\u{FF66}'..='\u{FF9F}' // 半角カタカナ
+    )
+}
+
+/// テンプレートから生成したプロンプト
+///
+/// `{text}` を含む行より前が指示文（チャットAPIではsystemメッセージ）、それ以降が本文になる。
+pub struct RenderedPrompt {
+    pub instructions: String,
+    pub body: String,
+}
+
+impl RenderedPrompt {
+    /// 1つのプロンプト文字列として結合する（Ollamaの `/api/generate` 用）
+    pub fn to_single_prompt(&self) -> String {
+        format!("{}\n{}", self.instructions, self.body)
+    }
+}
+
+/// 言語ごとのプロンプトテンプレートから個人情報検出用のプロンプトを生成する
+#[derive(Clone)]
+pub struct PromptBuilder {
+    templates: HashMap<Language, String>,
+    target_types: Vec<String>,
+    examples: Vec<PromptExample>,
+}
+
+impl PromptBuilder {
+    /// 組み込みの日本語・英語テンプレートを使う
+    pub fn new() -> Self {
+        Self::from_prompt_config(&PromptConfig::default())
+            .expect("built-in prompt templates must be valid")
+    }
+
+    /// 設定ファイルのテンプレート・検出対象・例を使う（テンプレート未指定の言語は組み込みのもの）
+    pub fn from_prompt_config(config: &PromptConfig) -> Result<Self, AppError> {
+        let mut templates = HashMap::from([
+            (Language::Japanese, JA_TEMPLATE.to_string()),
+            (Language::English, EN_TEMPLATE.to_string()),
+        ]);
+
+        for (code, path) in &config.templates {
+            let language = Language::from_code(code)
+                .ok_or_else(|| AppError::ConfigError(format!("Unknown prompt template language '{}' (expected ja or en)", code)))?;
+            let template = fs::read_to_string(path)
+                .map_err(|e| AppError::ConfigError(format!("Failed to read prompt template '{}': {}", path, e)))?;
+            if !template.contains(TEXT_PLACEHOLDER) {
+                return Err(AppError::ConfigError(format!("Prompt template '{}' has no {} placeholder", path, TEXT_PLACEHOLDER)));
+            }
+            templates.insert(language, template);
+        }
+
+        Ok(PromptBuilder {
+            templates,
+            target_types: config.target_types.clone(),
+            examples: config.examples.clone(),
+        })
+    }
+
+    /// テンプレート・検出対象・例から求めたプロンプトのバージョン（いずれかが変わると変わる）
+    pub fn version(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(PROMPT_FORMAT_VERSION);
+        for language in [Language::Japanese, Language::English] {
+            hasher.update(self.templates[&language].as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(self.target_types.join(",").as_bytes());
+        for example in &self.examples {
+            hasher.update([0]);
+            hasher.update(example.text.as_bytes());
+            hasher.update(example.output.to_string().as_bytes());
+        }
+        format!("{:x}", hasher.finalize())[..16].to_string()
+    }
+
+    /// テキストの言語に合ったテンプレートでプロンプトを生成する
+    pub fn render(&self, text: &str) -> RenderedPrompt {
+        let language = Language::detect(text);
+        let template = &self.templates[&language];
+
+        // `{text}` を含む行で指示文と本文に分ける
+        let split_at = template.find(TEXT_PLACEHOLDER)
+            .map_or(template.len(), |pos| template[..pos].rfind('\n').map_or(0, |nl| nl + 1));
+        let (instructions, body) = template.split_at(split_at);
+
+        let types = self.target_types.join(", ");
+        let examples = self.render_examples(language);
+        let instructions = instructions
+            .replace(TYPES_PLACEHOLDER, &types)
+            .replace(EXAMPLES_PLACEHOLDER, &examples);
+        // 本文は対象テキストを最後に埋め込み、テキスト中の `{...}` が置換されないようにする
+        let body = body
+            .replace(TYPES_PLACEHOLDER, &types)
+            .replace(EXAMPLES_PLACEHOLDER, &examples)
+            .replacen(TEXT_PLACEHOLDER, text, 1);
+
+        RenderedPrompt {
+            instructions: instructions.trim_end().to_string(),
+            body: body.trim_end().to_string(),
+        }
+    }
+
+    // few-shot例をテンプレートの言語で整形する
+    fn render_examples(&self, language: Language) -> String {
+        let (heading, text_label, output_label) = match language {
+            Language::Japanese => ("例", "テキスト", "出力"),
+            Language::English => ("Example", "Text", "Output"),
+        };
+
+        self.examples.iter()
+            .map(|example| format!("\n{}:\n{}:\n{}\n{}:\n{}\n", heading, text_label, example.text, output_label, example.output))
+            .collect()
+    }
+}
+
+/// 検出候補の確認を依頼するプロンプトを生成する
+///
+/// `candidates_json` は候補（id, type, value, context）のJSON配列で、本文としてそのまま送る。
+pub fn create_verification_prompt(candidates_json: &str, language: Language) -> RenderedPrompt {
+    let instructions = match language {
+        Language::Japanese => JA_VERIFY_INSTRUCTIONS,
+        Language::English => EN_VERIFY_INSTRUCTIONS,
+    };
+
+    RenderedPrompt {
+        instructions: instructions.to_string(),
+        body: candidates_json.to_string(),
+    }
+}
+
+impl Default for PromptBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_picks_language_and_fills_placeholders() {
+        let config = PromptConfig {
+            examples: vec![PromptExample {
+                text: "contact: bob@example.com".to_string(),
+                output: serde_json::json!({ "personal_information": [] }),
+            }],
+            ..PromptConfig::default()
+        };
+        let builder = PromptBuilder::from_prompt_config(&config).unwrap();
+
+        let english = builder.render("Please call John at 555-0100 about {text}.");
+        assert!(english.instructions.starts_with("Analyze the following text"));
+        assert!(english.instructions.contains("email"));
+        assert!(english.instructions.contains("Example:\nText:\ncontact: bob@example.com"));
+        assert_eq!(english.body, "Please call John at 555-0100 about {text}.");
+
+        let japanese = builder.render("山田太郎様の連絡先: taro@example.com");
+        assert!(japanese.instructions.starts_with("以下のテキストを解析し"));
+        assert!(japanese.body.ends_with("山田太郎様の連絡先: taro@example.com"));
+    }
+}
@@ -0,0 +1,220 @@
+use crate::infrastructure::error::AppError;
+use crate::utils::config::CacheConfig;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tempfile::NamedTempFile;
+
+// キャッシュファイルの拡張子
+const ENTRY_EXTENSION: &str = "json";
+
+// キャッシュ全体の件数と合計サイズ
+struct CacheUsage {
+    entries: usize,
+    bytes: u64,
+}
+
+/// LLMの応答をディスクに保存するキャッシュ
+///
+/// キーはテキスト・モデル名・プロンプトのバージョンのハッシュで、1件を1ファイルとして保存する。
+/// 件数か合計サイズが上限を超えると、最後に使われた時刻（ファイルの更新時刻）が古いものから削除する。
+pub struct ResponseCache {
+    dir: PathBuf,
+    max_entries: usize,
+    max_bytes: u64,
+    usage: Mutex<CacheUsage>,
+}
+
+impl ResponseCache {
+    /// キャッシュディレクトリを開く（なければ作成し、上限を超えていれば削除する）
+    pub fn open(config: &CacheConfig) -> Result<Self, AppError> {
+        let dir = config.dir.as_ref().map_or_else(default_cache_dir, PathBuf::from);
+        fs::create_dir_all(&dir)?;
+
+        let cache = ResponseCache {
+            dir,
+            max_entries: config.max_entries,
+            max_bytes: config.max_size_mb * 1024 * 1024,
+            usage: Mutex::new(CacheUsage { entries: 0, bytes: 0 }),
+        };
+        cache.evict()?;
+        Ok(cache)
+    }
+
+    /// キャッシュのキーを求める
+    pub fn key(text: &str, model: &str, prompt_version: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [text, model, prompt_version] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// 保存された応答を取得する（取得したエントリは最近使われたものとして扱う）
+    pub fn get(&self, key: &str) -> Option<String> {
+        let path = self.entry_path(key);
+        let value = fs::read_to_string(&path).ok()?;
+        if let Ok(file) = fs::File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(value)
+    }
+
+    /// 応答を保存し、上限を超えた場合は古いエントリを削除する
+    pub fn put(&self, key: &str, value: &str) -> Result<(), AppError> {
+        let path = self.entry_path(key);
+        let replaced = fs::metadata(&path).map(|metadata| metadata.len()).ok();
+
+        // 書き込み途中のファイルを読まないよう、一時ファイルに書いてから置き換える
+        // （同じキーを並行して保存しても衝突しないよう、一時ファイルは毎回別の名前にする）
+        let mut temp_file = NamedTempFile::new_in(&self.dir)?;
+        temp_file.write_all(value.as_bytes())?;
+        temp_file.persist(&path).map_err(|e| e.error)?;
+
+        let over_limit = {
+            let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+            match replaced {
+                Some(old_len) => usage.bytes = usage.bytes.saturating_sub(old_len),
+                None => usage.entries += 1,
+            }
+            usage.bytes += value.len() as u64;
+            usage.entries > self.max_entries || usage.bytes > self.max_bytes
+        };
+        if over_limit {
+            self.evict()?;
+        }
+        Ok(())
+    }
+
+    /// キャッシュの全エントリを削除し、削除した件数を返す
+    pub fn clear(&self) -> Result<usize, AppError> {
+        let entries = self.list_entries()?;
+        for (path, _, _) in &entries {
+            fs::remove_file(path)?;
+        }
+
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.entries = 0;
+        usage.bytes = 0;
+        Ok(entries.len())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // 上限に収まるまで最後に使われた時刻の古いエントリから削除する
+    fn evict(&self) -> Result<(), AppError> {
+        let mut entries = self.list_entries()?;
+        entries.sort_by_key(|(_, modified, _)| *modified);
+
+        let mut count = entries.len();
+        let mut bytes: u64 = entries.iter().map(|(_, _, len)| len).sum();
+        for (path, _, len) in &entries {
+            if count <= self.max_entries && bytes <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(path)?;
+            count -= 1;
+            bytes -= len;
+        }
+
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.entries = count;
+        usage.bytes = bytes;
+        Ok(())
+    }
+
+    // キャッシュのエントリ（パス, 更新時刻, サイズ）の一覧
+    fn list_entries(&self) -> Result<Vec<(PathBuf, SystemTime, u64)>, AppError> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+            let metadata = fs::metadata(&path)?;
+            entries.push((path, metadata.modified()?, metadata.len()));
+        }
+        Ok(entries)
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, ENTRY_EXTENSION))
+    }
+}
+
+/// 既定のキャッシュディレクトリ（`$XDG_CACHE_HOME/sec-scan` または `~/.cache/sec-scan`）
+pub fn default_cache_dir() -> PathBuf {
+    std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sec-scan")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_put_get_and_evict_least_recently_used() {
+        let dir = tempdir().unwrap();
+        let config = CacheConfig {
+            dir: Some(dir.path().to_string_lossy().into_owned()),
+            max_entries: 2,
+            ..CacheConfig::default()
+        };
+        let cache = ResponseCache::open(&config).unwrap();
+
+        let first = ResponseCache::key("text 1", "model", "v1");
+        let second = ResponseCache::key("text 2", "model", "v1");
+        let third = ResponseCache::key("text 3", "model", "v1");
+        assert_ne!(first, ResponseCache::key("text 1", "other-model", "v1"));
+
+        cache.put(&first, "response 1").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&second, "response 2").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        // 最初のエントリを使うと、2番目が最も古くなる
+        assert_eq!(cache.get(&first).as_deref(), Some("response 1"));
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&third, "response 3").unwrap();
+
+        assert!(cache.get(&first).is_some());
+        assert!(cache.get(&second).is_none());
+        assert!(cache.get(&third).is_some());
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert!(cache.get(&first).is_none());
+    }
+
+    #[test]
+    fn test_concurrent_puts_of_the_same_key() {
+        let dir = tempdir().unwrap();
+        let config = CacheConfig {
+            dir: Some(dir.path().to_string_lossy().into_owned()),
+            ..CacheConfig::default()
+        };
+        let cache = ResponseCache::open(&config).unwrap();
+        let key = ResponseCache::key("same text", "model", "v1");
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| cache.put(&key, "response").unwrap());
+            }
+        });
+        assert_eq!(cache.get(&key).as_deref(), Some("response"));
+        // 一時ファイルは残らない
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::infrastructure::error::AppError;
+use serde_json::{json, Value};
+
+/// LLMが返す検出結果（ApiDetectorの `ApiResponse`）のJSONスキーマ
+///
+/// Ollamaの構造化出力（`format` にスキーマを指定）に渡し、応答の検証にも使う。
+pub fn personal_info_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "personal_information": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "type": { "type": "string" },
+                        "value": { "type": "string" },
+                        "line": { "type": "integer", "minimum": 0 },
+                        "start": { "type": "integer", "minimum": 0 },
+                        "end": { "type": "integer", "minimum": 0 },
+                        "confidence": { "type": "number", "minimum": 0, "maximum": 1 }
+                    },
+                    "required": ["type", "value", "line", "start", "end"]
+                }
+            }
+        },
+        "required": ["personal_information"]
+    })
+}
+
+/// 検出候補の確認（verifyモード）でLLMが返す判定のJSONスキーマ
+pub fn verification_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "verdicts": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "integer", "minimum": 0 },
+                        "keep": { "type": "boolean" },
+                        "reason": { "type": "string" }
+                    },
+                    "required": ["id", "keep"]
+                }
+            }
+        },
+        "required": ["verdicts"]
+    })
+}
+
+/// LLMの応答がJSONとして解析でき、要求したスキーマに適合するかを検証する
+pub fn validate_response(response: &str, expected: &Value) -> Result<(), AppError> {
+    let value: Value = serde_json::from_str(response)
+        .map_err(|e| AppError::SchemaError(format!("response is not valid JSON: {}", e)))?;
+    validate(&value, expected).map_err(AppError::SchemaError)
+}
+
+/// 値がスキーマに適合するかを検証し、違反箇所をJSONパス付きで返す
+///
+/// `type`, `properties`, `required`, `items`, `minimum`, `maximum` のみを扱う簡易実装。
+pub fn validate(value: &Value, schema: &Value) -> Result<(), String> {
+    validate_at(value, schema, "$")
+}
+
+fn validate_at(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        let matches = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_u64() || value.is_i64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            return Err(format!("{}: expected {}, got {}", path, expected, type_name(value)));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                return Err(format!("{}: {} is less than minimum {}", path, number, minimum));
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                return Err(format!("{}: {} is greater than maximum {}", path, number, maximum));
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    return Err(format!("{}: missing required property '{}'", path, key));
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (key, property_schema) in properties {
+                if let Some(property) = object.get(key) {
+                    validate_at(property, property_schema, &format!("{}.{}", path, key))?;
+                }
+            }
+        }
+    }
+
+    if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+        for (idx, item) in items.iter().enumerate() {
+            validate_at(item, item_schema, &format!("{}[{}]", path, idx))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reports_path_of_violation() {
+        let schema = personal_info_schema();
+
+        let valid = json!({
+            "personal_information": [
+                { "type": "email", "value": "a@example.com", "line": 1, "start": 0, "end": 13, "confidence": 0.9 }
+            ]
+        });
+        assert!(validate(&valid, &schema).is_ok());
+
+        let wrong_type = json!({
+            "personal_information": [
+                { "type": "email", "value": "a@example.com", "line": "1", "start": 0, "end": 13 }
+            ]
+        });
+        assert_eq!(
+            validate(&wrong_type, &schema).unwrap_err(),
+            "$.personal_information[0].line: expected integer, got string"
+        );
+
+        let missing = json!({ "results": [] });
+        assert!(validate(&missing, &schema).unwrap_err().contains("'personal_information'"));
+    }
+}
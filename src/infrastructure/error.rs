@@ -6,9 +6,14 @@ use std::io;
 pub enum AppError {
     IoError(io::Error),
     ApiError(String),
+    SchemaError(String),
     PdfExtractError(String),
     DocxExtractError(String),
+    SpreadsheetExtractError(String),
+    PresentationExtractError(String),
+    ArchiveError(String),
     JsonError(serde_json::Error),
+    ConfigError(String),
     #[allow(dead_code)]
     NotImplemented,
     Other(Box<dyn StdError + Send + Sync>),
@@ -19,9 +24,14 @@ impl fmt::Display for AppError {
         match self {
             AppError::IoError(e) => write!(f, "IO Error: {}", e),
             AppError::ApiError(msg) => write!(f, "API Error: {}", msg),
+            AppError::SchemaError(msg) => write!(f, "Schema Error: {}", msg),
             AppError::PdfExtractError(msg) => write!(f, "PDF Extract Error: {}", msg),
             AppError::DocxExtractError(msg) => write!(f, "DOCX Extract Error: {}", msg),
+            AppError::SpreadsheetExtractError(msg) => write!(f, "Spreadsheet Extract Error: {}", msg),
+            AppError::PresentationExtractError(msg) => write!(f, "Presentation Extract Error: {}", msg),
+            AppError::ArchiveError(msg) => write!(f, "Archive Error: {}", msg),
             AppError::JsonError(e) => write!(f, "JSON Error: {}", e),
+            AppError::ConfigError(msg) => write!(f, "Config Error: {}", msg),
             AppError::NotImplemented => write!(f, "Feature not implemented"),
             AppError::Other(e) => write!(f, "Error: {}", e),
         }
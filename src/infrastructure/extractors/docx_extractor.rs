@@ -1,53 +1,306 @@
-use crate::domain::TextExtractor;
+use crate::domain::{ExtractedText, TextExtractor};
 use crate::infrastructure::error::AppError;
+use crate::infrastructure::extractors::office::{read_relationships, read_zip_text};
+use quick_xml::events::Event;
+use quick_xml::Reader;
 use std::error::Error as StdError;
 use std::path::Path;
 use std::fs::File;
-use std::io::Read;
+use std::io::{Cursor, Read, Seek};
 use zip::ZipArchive;
-use regex::Regex;
 
+/// DOCXの抽出器
+///
+/// 本文に加えてヘッダー・フッター・脚注・文末脚注・コメント・変更履歴で削除されたテキスト・
+/// `docProps/core.xml` の作成者などのメタデータを読み、段落ごとに `Header` や `Body (deleted)` のような位置を付ける。
 pub struct DocxExtractor;
 
+// 文書のどの部品か（出力はこの順に並べる）
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum DocumentPart {
+    Body,
+    Header,
+    Footer,
+    Footnotes,
+    Endnotes,
+    Comments,
+}
+
+impl DocumentPart {
+    fn label(self) -> &'static str {
+        match self {
+            DocumentPart::Body => "Body",
+            DocumentPart::Header => "Header",
+            DocumentPart::Footer => "Footer",
+            DocumentPart::Footnotes => "Footnotes",
+            DocumentPart::Endnotes => "Endnotes",
+            DocumentPart::Comments => "Comments",
+        }
+    }
+
+    // 本文からの関係の種類（`.../relationships/header` など）に対応する部品
+    fn from_relationship(type_: &str) -> Option<Self> {
+        match type_.rsplit('/').next()? {
+            "header" => Some(DocumentPart::Header),
+            "footer" => Some(DocumentPart::Footer),
+            "footnotes" => Some(DocumentPart::Footnotes),
+            "endnotes" => Some(DocumentPart::Endnotes),
+            "comments" => Some(DocumentPart::Comments),
+            _ => None,
+        }
+    }
+}
+
+// 段落の一部分（変更履歴で削除されたテキストかどうか）
+struct Segment {
+    deleted: bool,
+    text: String,
+}
+
+// メタデータとして読む `docProps/core.xml` の要素
+const CORE_PROPERTIES: &[&str] = &["creator", "lastModifiedBy", "title", "subject", "keywords", "description", "category"];
+
 impl TextExtractor for DocxExtractor {
-    fn supports(&self, file_type: &str) -> bool {
-        file_type.to_lowercase() == "docx"
+    fn extensions(&self) -> &'static [&'static str] {
+        &["docx"]
     }
-    
+
     fn extract(&self, file_path: &Path) -> Result<String, Box<dyn StdError + Send + Sync>> {
+        Ok(self.extract_with_locations(file_path)?.text)
+    }
+
+    fn extract_with_locations(&self, file_path: &Path) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
         // ファイルを開く
         let file = File::open(file_path)
-            .map_err(|e| AppError::IoError(e))?;
-        
-        // ZIPアーカイブとして開く
-        let mut archive = ZipArchive::new(file)
-            .map_err(|e| AppError::DocxExtractError(format!("Failed to open DOCX as ZIP: {}", e)))?;
-        
-        // document.xmlを取得（DOCXの本文）
-        let mut document_content = String::new();
-        if let Ok(mut document) = archive.by_name("word/document.xml") {
-            document.read_to_string(&mut document_content)
-                .map_err(|e| AppError::DocxExtractError(format!("Failed to read document.xml: {}", e)))?;
+            .map_err(AppError::IoError)?;
+
+        extract_package(file)
+    }
+
+    fn extract_bytes_with_locations(&self, data: &[u8]) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        extract_package(Cursor::new(data))
+    }
+}
+
+fn extract_package<R: Read + Seek>(reader: R) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+    // ZIPアーカイブとして開く
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| AppError::DocxExtractError(format!("Failed to open DOCX as ZIP: {}", e)))?;
+
+    read_document(&mut archive)
+        .map_err(|e| AppError::DocxExtractError(e).into())
+}
+
+// 本文・本文から参照される部品・メタデータを順に読む
+fn read_document<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<ExtractedText, String> {
+    let package_relationships = read_relationships(archive, "")?;
+    let document_path = package_relationships.iter()
+        .find(|rel| rel.type_.ends_with("/officeDocument"))
+        .map_or_else(|| "word/document.xml".to_string(), |rel| rel.target.clone());
+    let document = read_zip_text(archive, &document_path)?
+        .ok_or_else(|| "document.xml not found in DOCX".to_string())?;
+
+    let mut parts = vec![(DocumentPart::Body, document_path.clone(), document)];
+    let mut related: Vec<(DocumentPart, String)> = read_relationships(archive, &document_path)?
+        .into_iter()
+        .filter_map(|rel| DocumentPart::from_relationship(&rel.type_).map(|part| (part, rel.target)))
+        .collect();
+    related.sort();
+    related.dedup();
+    for (part, path) in related {
+        if let Some(xml) = read_zip_text(archive, &path)? {
+            parts.push((part, path, xml));
+        }
+    }
+
+    let mut extracted = ExtractedText::default();
+    for (part, path, xml) in &parts {
+        let paragraphs = parse_paragraphs(xml)
+            .map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+        for segments in paragraphs {
+            push_paragraph(&mut extracted, part.label(), &segments);
+        }
+    }
+
+    let core_path = package_relationships.iter()
+        .find(|rel| rel.type_.ends_with("/core-properties"))
+        .map_or_else(|| "docProps/core.xml".to_string(), |rel| rel.target.clone());
+    if let Some(xml) = read_zip_text(archive, &core_path)? {
+        for (name, value) in parse_core_properties(&xml).map_err(|e| format!("Failed to parse {}: {}", core_path, e))? {
+            extracted.push_labeled(&format!("Metadata ({})", name), &value);
+            extracted.end_line();
+        }
+    }
+
+    Ok(extracted)
+}
+
+// 段落を1行として追加する。削除されたテキストは `(deleted)` を付けた位置にし、前後と空白で区切る
+fn push_paragraph(extracted: &mut ExtractedText, label: &str, segments: &[Segment]) {
+    for (index, segment) in segments.iter().enumerate() {
+        if index > 0 {
+            extracted.push_str(" ");
+        }
+        if segment.deleted {
+            extracted.push_labeled(&format!("{} (deleted)", label), &segment.text);
         } else {
-            return Err(AppError::DocxExtractError("document.xml not found in DOCX".to_string()).into());
-        }
-        
-        // XMLからテキストを抽出（簡易実装）
-        // 正規表現で<w:t>...</w:t>タグ内のテキストを抽出
-        let re = Regex::new(r"<w:t[^>]*>(.*?)</w:t>").unwrap();
-        let mut text = String::new();
-        
-        for cap in re.captures_iter(&document_content) {
-            if let Some(matched) = cap.get(1) {
-                text.push_str(matched.as_str());
-                text.push(' '); // 単語間のスペースを追加
+            extracted.push_labeled(label, &segment.text);
+        }
+    }
+    extracted.end_line();
+}
+
+// WordprocessingML の段落（`<w:p>`）ごとのテキスト。表のセルやテキストボックスの段落も1段落として扱う
+fn parse_paragraphs(xml: &str) -> Result<Vec<Vec<Segment>>, String> {
+    let mut paragraphs = Vec::new();
+    // テキストボックスの段落は外側の段落の中に入れ子になる
+    let mut stack: Vec<Vec<Segment>> = Vec::new();
+    let mut run_depth = 0;
+    let mut deleted_depth = 0;
+    // 互換用の代替表現（`<mc:Fallback>`）は同じ内容の繰り返しなので読まない
+    let mut fallback_depth = 0;
+    let mut in_text = false;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        if fallback_depth > 0 {
+            match &event {
+                Event::Start(e) if e.local_name().as_ref() == b"Fallback" => fallback_depth += 1,
+                Event::End(e) if e.local_name().as_ref() == b"Fallback" => fallback_depth -= 1,
+                Event::Eof => break,
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"Fallback" => fallback_depth += 1,
+                b"p" => stack.push(Vec::new()),
+                b"r" => run_depth += 1,
+                b"del" => deleted_depth += 1,
+                b"t" | b"delText" => in_text = true,
+                _ => {}
+            },
+            Event::Empty(e) if run_depth > 0 => match e.local_name().as_ref() {
+                b"tab" => append(&mut stack, deleted_depth > 0, "\t"),
+                b"br" | b"cr" => append(&mut stack, deleted_depth > 0, " "),
+                b"noBreakHyphen" => append(&mut stack, deleted_depth > 0, "-"),
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"p" => {
+                    if let Some(segments) = stack.pop() {
+                        if segments.iter().any(|segment| !segment.text.trim().is_empty()) {
+                            paragraphs.push(segments);
+                        }
+                    }
+                }
+                b"r" if run_depth > 0 => run_depth -= 1,
+                b"del" if deleted_depth > 0 => deleted_depth -= 1,
+                b"t" | b"delText" => in_text = false,
+                _ => {}
+            },
+            Event::Text(t) if in_text => {
+                let text = t.unescape().map_err(|e| e.to_string())?;
+                append(&mut stack, deleted_depth > 0, &text);
+            }
+            Event::CData(t) if in_text => append(&mut stack, deleted_depth > 0, &String::from_utf8_lossy(&t)),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(paragraphs)
+}
+
+// 現在の段落にテキストを追加する（削除されたかどうかが同じなら直前の部分につなげる）
+fn append(stack: &mut [Vec<Segment>], deleted: bool, text: &str) {
+    let Some(segments) = stack.last_mut() else {
+        return;
+    };
+    match segments.last_mut() {
+        Some(segment) if segment.deleted == deleted => segment.text.push_str(text),
+        _ => segments.push(Segment { deleted, text: text.to_string() }),
+    }
+}
+
+// `docProps/core.xml` の作成者・最終更新者・タイトルなど（要素のローカル名, 値）
+fn parse_core_properties(xml: &str) -> Result<Vec<(String, String)>, String> {
+    let mut properties = Vec::new();
+    let mut current: Option<(String, String)> = None;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                if CORE_PROPERTIES.contains(&name.as_str()) {
+                    current = Some((name, String::new()));
+                }
+            }
+            Event::Text(t) => {
+                if let Some((_, value)) = current.as_mut() {
+                    value.push_str(&t.unescape().map_err(|e| e.to_string())?);
+                }
             }
+            Event::End(_) => {
+                if let Some((name, value)) = current.take() {
+                    if !value.trim().is_empty() {
+                        properties.push((name, value));
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(properties)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn test_docx_parts_tracked_changes_and_metadata() {
+        let entries = [
+            ("_rels/.rels", r#"<Relationships>
+                <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+                <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
+            </Relationships>"#),
+            ("word/document.xml", r#"<w:document xmlns:w="w" xmlns:mc="mc"><w:body>
+                <w:p><w:pPr><w:tabs><w:tab w:val="left" w:pos="720"/></w:tabs></w:pPr><w:r><w:t>担当:</w:t></w:r><w:r><w:tab/><w:t>山</w:t></w:r><w:r><w:t xml:space="preserve">田 &amp; 佐藤 &#x3042;</w:t></w:r></w:p>
+                <w:p><w:r><w:t>電話 </w:t></w:r><w:del w:author="x"><w:r><w:delText>090-1234-5678</w:delText></w:r></w:del><w:ins><w:r><w:t>非公開</w:t></w:r></w:ins></w:p>
+                <w:p><w:r><mc:AlternateContent><mc:Choice><w:txbxContent><w:p><w:r><w:t>枠内</w:t></w:r></w:p></w:txbxContent></mc:Choice><mc:Fallback><w:txbxContent><w:p><w:r><w:t>枠内</w:t></w:r></w:p></w:txbxContent></mc:Fallback></mc:AlternateContent></w:r></w:p>
+            </w:body></w:document>"#),
+            ("word/_rels/document.xml.rels", r#"<Relationships>
+                <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="comments.xml"/>
+                <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/header" Target="header1.xml"/>
+                <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/footnotes" Target="footnotes.xml"/>
+            </Relationships>"#),
+            ("word/header1.xml", r#"<w:hdr xmlns:w="w"><w:p><w:r><w:t>社外秘 a@example.com</w:t></w:r></w:p></w:hdr>"#),
+            ("word/footnotes.xml", r#"<w:footnotes xmlns:w="w"><w:footnote w:type="separator"><w:p><w:r><w:separator/></w:r></w:p></w:footnote><w:footnote w:id="1"><w:p><w:r><w:t>出典: 鈴木</w:t></w:r></w:p></w:footnote></w:footnotes>"#),
+            ("word/comments.xml", r#"<w:comments xmlns:w="w"><w:comment w:id="0" w:author="A"><w:p><w:r><w:t>要確認</w:t></w:r></w:p></w:comment></w:comments>"#),
+            ("docProps/core.xml", r#"<cp:coreProperties xmlns:cp="cp" xmlns:dc="dc"><dc:title>名簿</dc:title><dc:creator>高橋 一郎</dc:creator><cp:revision>3</cp:revision></cp:coreProperties>"#),
+        ];
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
         }
-        
-        // 段落の区切りを追加
-        let paragraph_re = Regex::new(r"</w:p>").unwrap();
-        let text = paragraph_re.replace_all(&text, "\n");
-        
-        Ok(text.to_string())
+        let mut archive = ZipArchive::new(writer.finish().unwrap()).unwrap();
+
+        let extracted = read_document(&mut archive).unwrap();
+        assert_eq!(extracted.text, "担当:\t山田 & 佐藤 あ\n電話  090-1234-5678 非公開\n枠内\n社外秘 a@example.com\n出典: 鈴木\n要確認\n名簿\n高橋 一郎\n");
+        assert_eq!(ExtractedText::location_at(&extracted.locations, 2, 8).map(|location| location.label.as_str()), Some("Body (deleted)"));
+        assert_eq!(ExtractedText::location_at(&extracted.locations, 2, 22).map(|location| location.label.as_str()), Some("Body"));
+        assert_eq!(ExtractedText::location_at(&extracted.locations, 4, 0).map(|location| location.label.as_str()), Some("Header"));
+        assert_eq!(ExtractedText::location_at(&extracted.locations, 5, 0).map(|location| location.label.as_str()), Some("Footnotes"));
+        assert_eq!(ExtractedText::location_at(&extracted.locations, 6, 0).map(|location| location.label.as_str()), Some("Comments"));
+        assert_eq!(ExtractedText::location_at(&extracted.locations, 8, 0).map(|location| location.label.as_str()), Some("Metadata (creator)"));
     }
-}
\ No newline at end of file
+}
@@ -1,16 +1,20 @@
-use crate::domain::TextExtractor;
+use crate::domain::{ExtractedText, TextExtractor};
 use crate::infrastructure::error::AppError;
+use std::collections::HashSet;
 use std::error::Error as StdError;
 use std::path::Path;
 
 pub struct ExtractorManager {
     extractors: Vec<Box<dyn TextExtractor>>,
+    // 設定でスキャン対象に指定された拡張子（Noneの場合は登録済みの抽出器が対応するすべて）
+    enabled_file_types: Option<HashSet<String>>,
 }
 
 impl ExtractorManager {
     pub fn new() -> Self {
         ExtractorManager {
             extractors: Vec::new(),
+            enabled_file_types: None,
         }
     }
     
@@ -18,7 +22,34 @@ impl ExtractorManager {
         self.extractors.push(Box::new(extractor));
     }
     
+    /// スキャン対象の拡張子を設定ファイルの `supported_file_types` に限定する
+    pub fn set_enabled_file_types(&mut self, file_types: &[String]) {
+        self.enabled_file_types = Some(file_types.iter()
+            .map(|file_type| file_type.trim_start_matches('.').to_lowercase())
+            .collect());
+    }
+    
+    /// 拡張子のファイルを抽出できるか（抽出器が登録されていて、対象として有効か）
+    pub fn supports_extension(&self, extension: &str) -> bool {
+        self.find_extractor(&extension.to_lowercase()).is_some()
+    }
+    
+    fn find_extractor(&self, extension: &str) -> Option<&dyn TextExtractor> {
+        if self.enabled_file_types.as_ref().is_some_and(|enabled| !enabled.contains(extension)) {
+            return None;
+        }
+        self.extractors.iter()
+            .find(|extractor| extractor.supports(extension))
+            .map(|extractor| extractor.as_ref())
+    }
+    
+    #[allow(dead_code)]
     pub fn extract(&self, file_path: &Path) -> Result<String, Box<dyn StdError + Send + Sync>> {
+        Ok(self.extract_with_locations(file_path)?.text)
+    }
+    
+    /// ファイルからテキストと位置情報を抽出する
+    pub fn extract_with_locations(&self, file_path: &Path) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
         // ファイル拡張子を取得
         let extension = file_path
             .extension()
@@ -27,28 +58,58 @@ impl ExtractorManager {
             .to_lowercase();
             
         // 拡張子に対応する抽出器を探す
-        for extractor in &self.extractors {
-            if extractor.supports(&extension) {
-                return extractor.extract(file_path);
-            }
+        match self.find_extractor(&extension) {
+            Some(extractor) => extractor.extract_with_locations(file_path),
+            // 対応する抽出器が見つからない場合はエラー
+            None => Err(AppError::Other(format!("No extractor found for file type: {}", extension).into()).into()),
         }
-        
-        // 対応する抽出器が見つからない場合はエラー
-        Err(AppError::Other(format!("No extractor found for file type: {}", extension).into()).into())
     }
     
+    /// メモリ上のデータ（アーカイブの中のファイルなど）から、拡張子に対応する抽出器でテキストと位置情報を抽出する
+    pub fn extract_bytes_with_locations(&self, extension: &str, data: &[u8]) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        let extension = extension.to_lowercase();
+        match self.find_extractor(&extension) {
+            Some(extractor) => extractor.extract_bytes_with_locations(data),
+            None => Err(AppError::Other(format!("No extractor found for file type: {}", extension).into()).into()),
+        }
+    }
+    
+    /// スキャン対象になる拡張子の一覧（登録順）
     #[allow(dead_code)]
     pub fn list_supported_extensions(&self) -> Vec<String> {
-        // テストのためにサポートしている拡張子のリストを返す
-        let mut extensions = Vec::new();
-        for ext in ["txt", "md", "csv", "pdf", "docx"] {
-            for extractor in &self.extractors {
-                if extractor.supports(ext) {
+        let mut extensions: Vec<String> = Vec::new();
+        for extractor in &self.extractors {
+            for ext in extractor.extensions() {
+                if self.supports_extension(ext) && !extensions.iter().any(|known| known == ext) {
                     extensions.push(ext.to_string());
-                    break;
                 }
             }
         }
         extensions
     }
-}
\ No newline at end of file
+}
+
+impl Default for ExtractorManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::extractors::{PdfExtractor, PlainTextExtractor};
+
+    #[test]
+    fn test_supported_extensions_follow_extractors_and_config() {
+        let mut manager = ExtractorManager::new();
+        manager.register(PlainTextExtractor);
+        assert_eq!(manager.list_supported_extensions(), vec!["txt", "md", "csv", "json", "xml", "html", "log"]);
+        assert!(!manager.supports_extension("pdf"));
+
+        manager.register(PdfExtractor);
+        manager.set_enabled_file_types(&["TXT".to_string(), ".json".to_string(), "pdf".to_string()]);
+        assert_eq!(manager.list_supported_extensions(), vec!["txt", "json", "pdf"]);
+        assert!(!manager.supports_extension("md"));
+    }
+}
@@ -1,9 +1,14 @@
 pub mod pdf_extractor;
 pub mod docx_extractor;
+pub mod spreadsheet_extractor;
+pub mod presentation_extractor;
 pub mod text_extractor;
 pub mod extractor_manager;
+mod office;
 
 pub use pdf_extractor::PdfExtractor;
 pub use docx_extractor::DocxExtractor;
+pub use spreadsheet_extractor::SpreadsheetExtractor;
+pub use presentation_extractor::PresentationExtractor;
 pub use text_extractor::PlainTextExtractor;
 pub use extractor_manager::ExtractorManager;
\ No newline at end of file
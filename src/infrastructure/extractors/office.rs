@@ -0,0 +1,130 @@
+// Office Open XML / OpenDocument 形式（ZIPに格納されたXML）を読むための共通処理
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::io::{Read, Seek};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+/// パッケージ内の部品間の関係（`_rels/*.rels` の Relationship）
+pub struct Relationship {
+    pub id: String,
+    pub type_: String,
+    /// パッケージ内の絶対パス（先頭の `/` なし）
+    pub target: String,
+}
+
+/// 1つの部品（XML）として読む展開後のサイズの上限（アーカイブの1ファイルの上限の既定値と同じ）
+const MAX_PART_BYTES: u64 = 100 * 1024 * 1024;
+
+/// ZIPのエントリをUTF-8の文字列として読む（エントリがない場合はNone）
+pub fn read_zip_text<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Option<String>, String> {
+    read_zip_text_limited(archive, name, MAX_PART_BYTES)
+}
+
+// 宣言されたサイズではなく実際に読んだ量で上限を確認する（展開すると巨大になる部品を読み切らない）
+fn read_zip_text_limited<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str, limit: u64) -> Result<Option<String>, String> {
+    let entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("Failed to open {}: {}", name, e)),
+    };
+
+    let mut content = String::new();
+    entry.take(limit + 1).read_to_string(&mut content)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    if content.len() as u64 > limit {
+        return Err(format!("{} exceeds the size limit of {} bytes", name, limit));
+    }
+    Ok(Some(content))
+}
+
+/// 要素の属性をローカル名で取得する（`r:id` などの名前空間の接頭辞は無視する）
+pub fn attribute(element: &BytesStart, local_name: &str) -> Option<String> {
+    element.attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == local_name.as_bytes())
+        .and_then(|attr| attr.unescape_value().ok().map(|value| value.into_owned()))
+}
+
+/// 関係のID（`r:id` 属性）。`<p:sldId id="256" r:id="rId2"/>` のように接頭辞のない `id` と並ぶことがある
+pub fn relationship_id(element: &BytesStart) -> Option<String> {
+    element.attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == b"id" && attr.key.prefix().is_some())
+        .and_then(|attr| attr.unescape_value().ok().map(|value| value.into_owned()))
+}
+
+/// 部品の関係ファイル（例: `xl/_rels/workbook.xml.rels`）を読み、ターゲットのパスを解決する
+pub fn read_relationships<R: Read + Seek>(archive: &mut ZipArchive<R>, part: &str) -> Result<Vec<Relationship>, String> {
+    let (dir, file_name) = match part.rfind('/') {
+        Some(pos) => (&part[..pos], &part[pos + 1..]),
+        None => ("", part),
+    };
+    let rels_path = if dir.is_empty() {
+        format!("_rels/{}.rels", file_name)
+    } else {
+        format!("{}/_rels/{}.rels", dir, file_name)
+    };
+    let Some(xml) = read_zip_text(archive, &rels_path)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut relationships = Vec::new();
+    let mut reader = Reader::from_str(&xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e) | Event::Empty(e)) if e.local_name().as_ref() == b"Relationship" => {
+                // 外部リンク（ハイパーリンクなど）はパッケージ内の部品ではないので除く
+                if attribute(&e, "TargetMode").as_deref() == Some("External") {
+                    continue;
+                }
+                if let (Some(id), Some(type_), Some(target)) = (attribute(&e, "Id"), attribute(&e, "Type"), attribute(&e, "Target")) {
+                    relationships.push(Relationship { id, type_, target: resolve_target(dir, &target) });
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("Failed to parse {}: {}", rels_path, e)),
+            _ => {}
+        }
+    }
+    Ok(relationships)
+}
+
+// 関係のターゲット（相対パスまたは `/` 始まりの絶対パス）をパッケージ内のパスに解決する
+fn resolve_target(base_dir: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        base_dir.split('/').filter(|part| !part.is_empty()).collect()
+    };
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn test_read_zip_text_rejects_oversized_part() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file("content.xml", FileOptions::default()).unwrap();
+        writer.write_all(&[b'a'; 32]).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(writer.finish().unwrap().into_inner())).unwrap();
+
+        assert_eq!(read_zip_text_limited(&mut archive, "content.xml", 32).unwrap().unwrap().len(), 32);
+        assert!(read_zip_text_limited(&mut archive, "content.xml", 31).unwrap_err().contains("size limit"));
+        assert!(read_zip_text_limited(&mut archive, "missing.xml", 31).unwrap().is_none());
+    }
+}
@@ -1,22 +1,286 @@
-use crate::domain::TextExtractor;
+use crate::domain::{ExtractedText, TextExtractor};
 use crate::infrastructure::error::AppError;
+use lopdf::{decode_text_string, Dictionary, Document, Object};
+use pdf_extract::{output_doc_page, PlainTextOutput};
 use std::error::Error as StdError;
 use std::path::Path;
 
+/// PDFの抽出器
+///
+/// 本文をページごとに読んで各行にページ番号を付け、注釈・文書情報（メタデータ）・フォームの入力値も
+/// `Page 2 (annotation)`・`Metadata (Author)`・`Form field (氏名)` のようなラベルの付いた部分として読む。
 pub struct PdfExtractor;
 
+// メタデータとして読む文書情報辞書のキー
+const INFO_KEYS: &[&str] = &["Title", "Author", "Subject", "Keywords", "Creator", "Producer"];
+
+// フォームフィールドの階層をたどる深さの上限（壊れた文書の循環参照対策）
+const MAX_FIELD_DEPTH: usize = 32;
+
 impl TextExtractor for PdfExtractor {
-    fn supports(&self, file_type: &str) -> bool {
-        file_type.to_lowercase() == "pdf"
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pdf"]
     }
-    
+
     fn extract(&self, file_path: &Path) -> Result<String, Box<dyn StdError + Send + Sync>> {
-        // ファイルパスのチェック（使用されないが、無効な場合はエラーを返す）
-        file_path.to_str()
-            .ok_or_else(|| AppError::Other("Invalid file path".into()))?;
-            
-        // PDFからテキストを抽出
-        pdf_extract::extract_text(file_path)
-            .map_err(|e| AppError::PdfExtractError(e.to_string()).into())
-    }
-}
\ No newline at end of file
+        Ok(self.extract_with_locations(file_path)?.text)
+    }
+
+    fn extract_with_locations(&self, file_path: &Path) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        let mut document = Document::load(file_path)
+            .map_err(|e| AppError::PdfExtractError(e.to_string()))?;
+
+        read_document(&mut document)
+            .map_err(|e| AppError::PdfExtractError(e).into())
+    }
+
+    fn extract_bytes_with_locations(&self, data: &[u8]) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        let mut document = Document::load_mem(data)
+            .map_err(|e| AppError::PdfExtractError(e.to_string()))?;
+
+        read_document(&mut document)
+            .map_err(|e| AppError::PdfExtractError(e).into())
+    }
+}
+
+// ページごとの本文と注釈、文書情報、フォームの入力値を順に読む
+fn read_document(document: &mut Document) -> Result<ExtractedText, String> {
+    if document.is_encrypted() {
+        // 所有者パスワードだけが設定された文書は空のパスワードで復号できる
+        document.decrypt("")
+            .map_err(|e| format!("Failed to decrypt PDF: {}", e))?;
+    }
+
+    let mut extracted = ExtractedText::default();
+    for (page_number, page_id) in document.get_pages() {
+        let label = format!("Page {}", page_number);
+        for line in page_text(document, page_number)?.lines().filter(|line| !line.trim().is_empty()) {
+            extracted.push_on_page(Some(page_number), &label, line);
+            extracted.end_line();
+        }
+
+        let annotation_label = format!("Page {} (annotation)", page_number);
+        for annotation in document.get_page_annotations(page_id).unwrap_or_default() {
+            for text in annotation_texts(document, annotation) {
+                extracted.push_on_page(Some(page_number), &annotation_label, &text);
+                extracted.end_line();
+            }
+        }
+    }
+
+    for (key, value) in document_info(document) {
+        extracted.push_labeled(&format!("Metadata ({})", key), &value);
+        extracted.end_line();
+    }
+
+    for (name, value) in form_fields(document) {
+        extracted.push_labeled(&format!("Form field ({})", name), &value);
+        extracted.end_line();
+    }
+
+    Ok(extracted)
+}
+
+// 1ページ分の本文
+fn page_text(document: &Document, page_number: u32) -> Result<String, String> {
+    let mut text = String::new();
+    {
+        let mut output = PlainTextOutput::new(&mut text);
+        output_doc_page(document, &mut output, page_number)
+            .map_err(|e| format!("Failed to extract text from page {}: {}", page_number, e))?;
+    }
+    Ok(text)
+}
+
+// 注釈の本文・作成者・件名とリンク先のURI。フォームのウィジェットはフォームの入力値として、
+// ポップアップは親の注釈と同じ内容なので読まない
+fn annotation_texts(document: &Document, annotation: &Dictionary) -> Vec<String> {
+    let subtype = annotation.get(b"Subtype").and_then(Object::as_name_str).unwrap_or_default();
+    if subtype == "Widget" || subtype == "Popup" {
+        return Vec::new();
+    }
+
+    let mut texts: Vec<String> = [b"T".as_slice(), b"Subj", b"Contents"].iter()
+        .filter_map(|key| annotation.get(key).ok().and_then(|value| text_value(document, value)))
+        .collect();
+    let uri = annotation.get(b"A").ok()
+        .and_then(|action| document.dereference(action).ok())
+        .and_then(|(_, action)| action.as_dict().ok())
+        .and_then(|action| action.get(b"URI").ok())
+        .and_then(|uri| text_value(document, uri));
+    texts.extend(uri);
+    texts
+}
+
+// 文書情報辞書（trailer の /Info）の作成者・タイトルなど
+fn document_info(document: &Document) -> Vec<(&'static str, String)> {
+    let Some(info) = document.trailer.get(b"Info").ok()
+        .and_then(|info| document.dereference(info).ok())
+        .and_then(|(_, info)| info.as_dict().ok())
+    else {
+        return Vec::new();
+    };
+
+    INFO_KEYS.iter()
+        .filter_map(|key| {
+            let value = info.get(key.as_bytes()).ok()?;
+            Some((*key, text_value(document, value)?))
+        })
+        .collect()
+}
+
+// AcroForm のフィールドの（完全修飾名, 入力値）
+fn form_fields(document: &Document) -> Vec<(String, String)> {
+    let Some(fields) = document.catalog().ok()
+        .and_then(|catalog| catalog.get(b"AcroForm").ok())
+        .and_then(|form| document.dereference(form).ok())
+        .and_then(|(_, form)| form.as_dict().ok())
+        .and_then(|form| form.get(b"Fields").ok())
+        .and_then(|fields| document.dereference(fields).ok())
+        .and_then(|(_, fields)| fields.as_array().ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut values = Vec::new();
+    for field in fields {
+        collect_field(document, field, "", 0, &mut values);
+    }
+    values
+}
+
+fn collect_field(document: &Document, field: &Object, parent_name: &str, depth: usize, values: &mut Vec<(String, String)>) {
+    if depth > MAX_FIELD_DEPTH {
+        return;
+    }
+    let Some(field) = document.dereference(field).ok().and_then(|(_, field)| field.as_dict().ok()) else {
+        return;
+    };
+
+    let name = match field.get(b"T").ok().and_then(|name| text_value(document, name)) {
+        Some(partial) if parent_name.is_empty() => partial,
+        Some(partial) => format!("{}.{}", parent_name, partial),
+        None => parent_name.to_string(),
+    };
+
+    if let Some(value) = field.get(b"V").ok().and_then(|value| text_value(document, value)) {
+        values.push((name.clone(), value));
+    }
+
+    let kids = field.get(b"Kids").ok()
+        .and_then(|kids| document.dereference(kids).ok())
+        .and_then(|(_, kids)| kids.as_array().ok());
+    for kid in kids.into_iter().flatten() {
+        collect_field(document, kid, &name, depth + 1, values);
+    }
+}
+
+// 文字列・名前・それらの配列（複数選択のフォーム値）をテキストにする
+fn text_value(document: &Document, value: &Object) -> Option<String> {
+    let (_, value) = document.dereference(value).ok()?;
+    let text = match value {
+        Object::String(..) => decode_text_string(value).ok()?,
+        Object::Name(name) => String::from_utf8_lossy(name).into_owned(),
+        Object::Array(items) => items.iter()
+            .filter_map(|item| text_value(document, item))
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => return None,
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Stream, StringFormat};
+
+    // 2ページ・注釈・文書情報・フォームを持つPDF
+    fn build_document() -> Document {
+        let mut document = Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+        let font_id = document.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+        let resources_id = document.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let mut page_ids = Vec::new();
+        for text in ["Cover page", "Contact: taro@example.com"] {
+            let content = Content {
+                operations: vec![
+                    Operation::new("BT", vec![]),
+                    Operation::new("Tf", vec!["F1".into(), 12.into()]),
+                    Operation::new("Td", vec![72.into(), 700.into()]),
+                    Operation::new("Tj", vec![Object::string_literal(text)]),
+                    Operation::new("ET", vec![]),
+                ],
+            };
+            let content_id = document.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            page_ids.push(document.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            }));
+        }
+
+        let annotation_id = document.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Text",
+            "T" => Object::String(lopdf::encode_utf16_be("山田太郎"), StringFormat::Hexadecimal),
+            "Contents" => Object::string_literal("Call 03-1234-5678"),
+        });
+        document.get_dictionary_mut(page_ids[1]).unwrap().set("Annots", vec![annotation_id.into()]);
+
+        document.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => page_ids.iter().map(|id| Object::Reference(*id)).collect::<Vec<_>>(),
+            "Count" => page_ids.len() as i64,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }));
+
+        let field_id = document.add_object(dictionary! {
+            "T" => Object::string_literal("applicant"),
+            "Kids" => vec![Object::Dictionary(dictionary! {
+                "T" => Object::string_literal("email"),
+                "V" => Object::string_literal("hanako@example.com"),
+            })],
+        });
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "AcroForm" => dictionary! { "Fields" => vec![field_id.into()] },
+        });
+        let info_id = document.add_object(dictionary! {
+            "Author" => Object::string_literal("Suzuki"),
+        });
+        document.trailer.set("Root", catalog_id);
+        document.trailer.set("Info", info_id);
+        document
+    }
+
+    #[test]
+    fn test_pages_annotations_metadata_and_form_fields() {
+        let mut document = build_document();
+        let extracted = read_document(&mut document).unwrap();
+
+        let lines: Vec<(&str, Option<u32>, &str)> = extracted.locations.iter()
+            .zip(extracted.text.lines())
+            .map(|(location, line)| (location.label.as_str(), location.page, line.trim()))
+            .collect();
+        assert_eq!(lines, vec![
+            ("Page 1", Some(1), "Cover page"),
+            ("Page 2", Some(2), "Contact: taro@example.com"),
+            ("Page 2 (annotation)", Some(2), "山田太郎"),
+            ("Page 2 (annotation)", Some(2), "Call 03-1234-5678"),
+            ("Metadata (Author)", None, "Suzuki"),
+            ("Form field (applicant.email)", None, "hanako@example.com"),
+        ]);
+    }
+}
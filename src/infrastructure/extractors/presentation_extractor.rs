@@ -0,0 +1,335 @@
+use crate::domain::{ExtractedText, TextExtractor};
+use crate::infrastructure::error::AppError;
+use crate::infrastructure::extractors::office::{attribute, read_relationships, read_zip_text, relationship_id};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// プレゼンテーション（pptx/odp）の抽出器
+///
+/// スライドの本文・表・発表者ノート・コメントを段落ごとに1行ずつ読み、`Slide 3` や `Slide 3 (notes)` のような位置を付ける。
+/// 非表示のスライドも読む。
+pub struct PresentationExtractor;
+
+// 段落がスライドのどこにあるか
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum SlidePart {
+    Body,
+    Notes,
+    Comment,
+}
+
+// 1枚のスライドの段落
+struct Slide {
+    number: usize,
+    paragraphs: Vec<(SlidePart, String)>,
+}
+
+impl TextExtractor for PresentationExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pptx", "odp"]
+    }
+
+    fn extract(&self, file_path: &Path) -> Result<String, Box<dyn StdError + Send + Sync>> {
+        Ok(self.extract_with_locations(file_path)?.text)
+    }
+
+    fn extract_with_locations(&self, file_path: &Path) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        let file = File::open(file_path)
+            .map_err(AppError::IoError)?;
+
+        extract_package(file)
+    }
+
+    fn extract_bytes_with_locations(&self, data: &[u8]) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        extract_package(Cursor::new(data))
+    }
+}
+
+fn extract_package<R: Read + Seek>(reader: R) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| AppError::PresentationExtractError(format!("Failed to open presentation as ZIP: {}", e)))?;
+
+    let slides = read_slides(&mut archive)
+        .map_err(AppError::PresentationExtractError)?;
+    Ok(render(&slides))
+}
+
+// パッケージの形式（OOXML か OpenDocument か）を判定してスライドを読む
+fn read_slides<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Vec<Slide>, String> {
+    let presentation = read_relationships(archive, "")?
+        .into_iter()
+        .find(|rel| rel.type_.ends_with("/officeDocument"))
+        .map(|rel| rel.target);
+    if let Some(presentation) = presentation {
+        return read_pptx(archive, &presentation);
+    }
+
+    match read_zip_text(archive, "content.xml")? {
+        Some(content) => read_odp(&content),
+        None => Err("Neither an OOXML presentation nor an OpenDocument presentation".to_string()),
+    }
+}
+
+// 段落を1行ずつ、スライド番号と部分を位置として並べる
+fn render(slides: &[Slide]) -> ExtractedText {
+    let mut extracted = ExtractedText::default();
+    for slide in slides {
+        for (part, text) in &slide.paragraphs {
+            let label = match part {
+                SlidePart::Body => format!("Slide {}", slide.number),
+                SlidePart::Notes => format!("Slide {} (notes)", slide.number),
+                SlidePart::Comment => format!("Slide {} (comment)", slide.number),
+            };
+            extracted.push_labeled(&label, text);
+            extracted.end_line();
+        }
+    }
+    extracted
+}
+
+// ---- pptx ----
+
+fn read_pptx<R: Read + Seek>(archive: &mut ZipArchive<R>, presentation_path: &str) -> Result<Vec<Slide>, String> {
+    let presentation = read_zip_text(archive, presentation_path)?
+        .ok_or_else(|| format!("{} not found", presentation_path))?;
+    let targets: HashMap<String, String> = read_relationships(archive, presentation_path)?
+        .into_iter()
+        .map(|rel| (rel.id, rel.target))
+        .collect();
+
+    // スライドの順番は presentation.xml の sldIdLst の順（非表示のスライドも含む）
+    let mut slide_paths = Vec::new();
+    let mut reader = Reader::from_str(&presentation);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e) | Event::Empty(e)) if e.local_name().as_ref() == b"sldId" => {
+                if let Some(path) = relationship_id(&e).and_then(|id| targets.get(&id).cloned()) {
+                    slide_paths.push(path);
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("Failed to parse {}: {}", presentation_path, e)),
+            _ => {}
+        }
+    }
+
+    let mut slides = Vec::new();
+    for (index, path) in slide_paths.iter().enumerate() {
+        let Some(xml) = read_zip_text(archive, path)? else {
+            continue;
+        };
+
+        let mut slide = Slide { number: index + 1, paragraphs: Vec::new() };
+        slide.paragraphs.extend(parse_paragraphs(&xml)?.into_iter().map(|text| (SlidePart::Body, text)));
+        for rel in read_relationships(archive, path)? {
+            let part = if rel.type_.ends_with("/notesSlide") {
+                SlidePart::Notes
+            } else if rel.type_.ends_with("/comments") {
+                SlidePart::Comment
+            } else {
+                continue;
+            };
+            if let Some(xml) = read_zip_text(archive, &rel.target)? {
+                slide.paragraphs.extend(parse_paragraphs(&xml)?.into_iter().map(|text| (part, text)));
+            }
+        }
+        slides.push(slide);
+    }
+    Ok(slides)
+}
+
+// DrawingML の段落（`<a:p>`、表のセルの段落も含む）と旧形式のコメント（`<p:text>`）のテキスト。
+// スライド番号や日付のフィールド（`<a:fld>`）は除く
+fn parse_paragraphs(xml: &str) -> Result<Vec<String>, String> {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut field_depth = 0;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"t" => in_text = field_depth == 0,
+                b"text" => in_text = true,
+                b"fld" => field_depth += 1,
+                _ => {}
+            },
+            Ok(Event::Empty(e)) if e.local_name().as_ref() == b"br" => current.push(' '),
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"p" | b"text" => {
+                    in_text = false;
+                    if !current.trim().is_empty() {
+                        paragraphs.push(std::mem::take(&mut current));
+                    }
+                    current.clear();
+                }
+                b"t" => in_text = false,
+                b"fld" => field_depth -= 1,
+                _ => {}
+            },
+            Ok(Event::Text(t)) if in_text => {
+                current.push_str(&t.unescape().map_err(|e| format!("Failed to parse slide: {}", e))?);
+            }
+            Ok(Event::CData(t)) if in_text => current.push_str(&String::from_utf8_lossy(&t)),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("Failed to parse slide: {}", e)),
+            _ => {}
+        }
+    }
+    Ok(paragraphs)
+}
+
+// ---- odp ----
+
+// `content.xml` の各ページ（`draw:page`）の段落・ノート・コメントを読む
+fn read_odp(xml: &str) -> Result<Vec<Slide>, String> {
+    let mut slides: Vec<Slide> = Vec::new();
+    let mut current = String::new();
+    let mut paragraph_depth = 0;
+    let mut notes_depth = 0;
+    let mut annotation_depth = 0;
+    let mut metadata_depth = 0;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        let event = reader.read_event()
+            .map_err(|e| format!("Failed to parse content.xml: {}", e))?;
+        match event {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"page" => slides.push(Slide { number: slides.len() + 1, paragraphs: Vec::new() }),
+                b"notes" => notes_depth += 1,
+                b"annotation" => annotation_depth += 1,
+                // コメントの作成者・日時は本文に含めない
+                b"creator" | b"date" if annotation_depth > 0 => metadata_depth += 1,
+                b"p" | b"h" => paragraph_depth += 1,
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"page" => slides.push(Slide { number: slides.len() + 1, paragraphs: Vec::new() }),
+                b"s" | b"tab" | b"line-break" if paragraph_depth > 0 => {
+                    let count = if e.local_name().as_ref() == b"s" {
+                        attribute(&e, "c").and_then(|c| c.parse::<usize>().ok()).unwrap_or(1)
+                    } else {
+                        1
+                    };
+                    current.extend(std::iter::repeat_n(' ', count));
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"notes" if notes_depth > 0 => notes_depth -= 1,
+                b"annotation" if annotation_depth > 0 => annotation_depth -= 1,
+                b"creator" | b"date" if metadata_depth > 0 => metadata_depth -= 1,
+                b"p" | b"h" if paragraph_depth > 0 => {
+                    paragraph_depth -= 1;
+                    if paragraph_depth == 0 {
+                        let part = if annotation_depth > 0 {
+                            SlidePart::Comment
+                        } else if notes_depth > 0 {
+                            SlidePart::Notes
+                        } else {
+                            SlidePart::Body
+                        };
+                        let text = std::mem::take(&mut current);
+                        if let (Some(slide), false) = (slides.last_mut(), text.trim().is_empty()) {
+                            slide.paragraphs.push((part, text));
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(t) if paragraph_depth > 0 && metadata_depth == 0 => {
+                current.push_str(&t.unescape().map_err(|e| format!("Failed to parse content.xml: {}", e))?);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(slides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn write_zip(entries: &[(&str, &str)]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    fn labeled_lines(extracted: &ExtractedText) -> Vec<(String, String)> {
+        extracted.locations.iter()
+            .zip(extracted.text.lines())
+            .map(|(location, line)| (location.label.clone(), line.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_pptx_slides_tables_notes_and_comments() {
+        let mut archive = write_zip(&[
+            ("_rels/.rels", r#"<Relationships><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="ppt/presentation.xml"/></Relationships>"#),
+            ("ppt/presentation.xml", r#"<p:presentation xmlns:p="p" xmlns:r="r"><p:sldIdLst><p:sldId id="256" r:id="rId3"/><p:sldId id="257" r:id="rId2"/></p:sldIdLst></p:presentation>"#),
+            ("ppt/_rels/presentation.xml.rels", r#"<Relationships>
+                <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/>
+                <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide2.xml"/>
+            </Relationships>"#),
+            ("ppt/slides/slide2.xml", r#"<p:sld xmlns:p="p" xmlns:a="a"><p:cSld><p:spTree>
+                <p:sp><p:txBody><a:p><a:r><a:t>顧客: 山田</a:t></a:r><a:r><a:t> 太郎</a:t></a:r></a:p><a:p><a:fld type="slidenum"><a:t>1</a:t></a:fld></a:p></p:txBody></p:sp>
+                <p:graphicFrame><a:graphic><a:graphicData><a:tbl><a:tr><a:tc><a:txBody><a:p><a:r><a:t>TEL 090-1234-5678</a:t></a:r></a:p></a:txBody></a:tc></a:tr></a:tbl></a:graphicData></a:graphic></p:graphicFrame>
+            </p:spTree></p:cSld></p:sld>"#),
+            ("ppt/slides/_rels/slide2.xml.rels", r#"<Relationships>
+                <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide1.xml"/>
+                <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments/comment1.xml"/>
+            </Relationships>"#),
+            ("ppt/notesSlides/notesSlide1.xml", r#"<p:notes xmlns:p="p" xmlns:a="a"><p:txBody><a:p><a:r><a:t>連絡先は a&amp;b@example.com</a:t></a:r></a:p></p:txBody></p:notes>"#),
+            ("ppt/comments/comment1.xml", r#"<p:cmLst xmlns:p="p"><p:cm authorId="0"><p:text>公開前に削除</p:text></p:cm></p:cmLst>"#),
+            ("ppt/slides/slide1.xml", r#"<p:sld xmlns:p="p" xmlns:a="a" show="0"><p:txBody><a:p><a:r><a:t>非表示</a:t></a:r></a:p></p:txBody></p:sld>"#),
+        ]);
+
+        let extracted = render(&read_slides(&mut archive).unwrap());
+        assert_eq!(labeled_lines(&extracted), vec![
+            ("Slide 1".to_string(), "顧客: 山田 太郎".to_string()),
+            ("Slide 1".to_string(), "TEL 090-1234-5678".to_string()),
+            ("Slide 1 (notes)".to_string(), "連絡先は a&b@example.com".to_string()),
+            ("Slide 1 (comment)".to_string(), "公開前に削除".to_string()),
+            ("Slide 2".to_string(), "非表示".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_odp_pages_notes_and_annotations() {
+        let mut archive = write_zip(&[
+            ("mimetype", "application/vnd.oasis.opendocument.presentation"),
+            ("content.xml", r#"<office:document-content xmlns:office="o" xmlns:draw="d" xmlns:text="t" xmlns:presentation="p" xmlns:dc="dc"><office:body><office:presentation>
+                <draw:page draw:name="page1"><draw:frame><draw:text-box><text:p>表紙</text:p></draw:text-box></draw:frame></draw:page>
+                <draw:page draw:name="page2">
+                    <draw:frame><draw:text-box><text:p>担当<text:s/>佐藤</text:p></draw:text-box></draw:frame>
+                    <office:annotation><dc:creator>レビュー担当</dc:creator><dc:date>2024-01-01</dc:date><text:p>電話番号を確認</text:p></office:annotation>
+                    <presentation:notes><draw:page-thumbnail/><draw:frame><draw:text-box><text:p>03-1234-5678</text:p></draw:text-box></draw:frame></presentation:notes>
+                </draw:page>
+            </office:presentation></office:body></office:document-content>"#),
+        ]);
+
+        let extracted = render(&read_slides(&mut archive).unwrap());
+        assert_eq!(labeled_lines(&extracted), vec![
+            ("Slide 1".to_string(), "表紙".to_string()),
+            ("Slide 2".to_string(), "担当 佐藤".to_string()),
+            ("Slide 2 (comment)".to_string(), "電話番号を確認".to_string()),
+            ("Slide 2 (notes)".to_string(), "03-1234-5678".to_string()),
+        ]);
+    }
+}
@@ -0,0 +1,575 @@
+use crate::domain::{ExtractedText, TextExtractor};
+use crate::infrastructure::error::AppError;
+use crate::infrastructure::extractors::office::{attribute, read_relationships, read_zip_text, relationship_id};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// スプレッドシート（xlsx/xlsm/ods）の抽出器
+///
+/// 非表示のシートやセルのコメントも含めてすべてのシートを読み、各セルに `Customers!C12` のような位置を付ける。
+/// 1行のセルはタブで区切って1行のテキストにする。
+pub struct SpreadsheetExtractor;
+
+// シートの内容
+struct Sheet {
+    name: String,
+    cells: Vec<Cell>,
+    comments: Vec<Cell>,
+}
+
+impl Sheet {
+    fn new(name: String) -> Self {
+        Sheet { name, cells: Vec::new(), comments: Vec::new() }
+    }
+}
+
+// 1つのセル（またはセルのコメント）の値。行・列は1始まり
+struct Cell {
+    row: u32,
+    column: u32,
+    text: String,
+}
+
+impl TextExtractor for SpreadsheetExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xlsx", "xlsm", "ods"]
+    }
+
+    fn extract(&self, file_path: &Path) -> Result<String, Box<dyn StdError + Send + Sync>> {
+        Ok(self.extract_with_locations(file_path)?.text)
+    }
+
+    fn extract_with_locations(&self, file_path: &Path) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        let file = File::open(file_path)
+            .map_err(AppError::IoError)?;
+
+        extract_package(file)
+    }
+
+    fn extract_bytes_with_locations(&self, data: &[u8]) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        extract_package(Cursor::new(data))
+    }
+}
+
+fn extract_package<R: Read + Seek>(reader: R) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| AppError::SpreadsheetExtractError(format!("Failed to open spreadsheet as ZIP: {}", e)))?;
+
+    let sheets = read_sheets(&mut archive)
+        .map_err(AppError::SpreadsheetExtractError)?;
+    Ok(render(&sheets))
+}
+
+// パッケージの形式（OOXML か OpenDocument か）を判定してシートを読む
+fn read_sheets<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Vec<Sheet>, String> {
+    let workbook = read_relationships(archive, "")?
+        .into_iter()
+        .find(|rel| rel.type_.ends_with("/officeDocument"))
+        .map(|rel| rel.target);
+    if let Some(workbook) = workbook {
+        return read_xlsx(archive, &workbook);
+    }
+
+    match read_zip_text(archive, "content.xml")? {
+        Some(content) => read_ods(&content),
+        None => Err("Neither an OOXML workbook nor an OpenDocument spreadsheet".to_string()),
+    }
+}
+
+// シートごとにセルを1行ずつ、コメントを1件ずつ並べたテキストにする
+fn render(sheets: &[Sheet]) -> ExtractedText {
+    let mut extracted = ExtractedText::default();
+    for sheet in sheets {
+        let mut current_row = None;
+        for cell in &sheet.cells {
+            if current_row == Some(cell.row) {
+                extracted.push_str("\t");
+            } else {
+                if current_row.is_some() {
+                    extracted.end_line();
+                }
+                current_row = Some(cell.row);
+            }
+            extracted.push_labeled(&cell_label(&sheet.name, cell), &cell.text);
+        }
+        if current_row.is_some() {
+            extracted.end_line();
+        }
+
+        for comment in &sheet.comments {
+            extracted.push_labeled(&format!("{} (comment)", cell_label(&sheet.name, comment)), &comment.text);
+            extracted.end_line();
+        }
+    }
+    extracted
+}
+
+// `Customers!C12` 形式の位置（英数字以外を含むシート名は `'My Sheet'!C12` のように引用符で囲む）
+fn cell_label(sheet_name: &str, cell: &Cell) -> String {
+    let reference = format!("{}{}", column_name(cell.column), cell.row);
+    if sheet_name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        format!("{}!{}", sheet_name, reference)
+    } else {
+        format!("'{}'!{}", sheet_name.replace('\'', "''"), reference)
+    }
+}
+
+// 列番号（1始まり）を A, B, ..., Z, AA, ... に変換する
+fn column_name(mut column: u32) -> String {
+    let mut name = Vec::new();
+    while column > 0 {
+        column -= 1;
+        name.push(b'A' + (column % 26) as u8);
+        column /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+// `C12` のようなセル参照を (行, 列) に変換する
+fn parse_cell_reference(reference: &str) -> Option<(u32, u32)> {
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let column = letters.to_ascii_uppercase()
+        .bytes()
+        .try_fold(0u32, |acc, b| acc.checked_mul(26)?.checked_add((b - b'A' + 1) as u32))?;
+    Some((digits.parse().ok()?, column))
+}
+
+// ---- xlsx / xlsm ----
+
+fn read_xlsx<R: Read + Seek>(archive: &mut ZipArchive<R>, workbook_path: &str) -> Result<Vec<Sheet>, String> {
+    let workbook = read_zip_text(archive, workbook_path)?
+        .ok_or_else(|| format!("{} not found", workbook_path))?;
+    let relationships = read_relationships(archive, workbook_path)?;
+
+    let shared_strings = match relationships.iter().find(|rel| rel.type_.ends_with("/sharedStrings")) {
+        Some(rel) => match read_zip_text(archive, &rel.target)? {
+            Some(xml) => parse_shared_strings(&xml)?,
+            None => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+    let targets: HashMap<&str, &str> = relationships.iter()
+        .map(|rel| (rel.id.as_str(), rel.target.as_str()))
+        .collect();
+
+    // 非表示（state="hidden" / "veryHidden"）のシートも区別せずに読む
+    let mut sheets = Vec::new();
+    let mut reader = Reader::from_str(&workbook);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e) | Event::Empty(e)) if e.local_name().as_ref() == b"sheet" => {
+                let name = attribute(&e, "name").unwrap_or_default();
+                let Some(path) = relationship_id(&e).and_then(|id| targets.get(id.as_str()).map(|target| target.to_string())) else {
+                    continue;
+                };
+                let Some(xml) = read_zip_text(archive, &path)? else {
+                    continue;
+                };
+
+                let mut sheet = Sheet::new(name);
+                sheet.cells = parse_worksheet(&xml, &shared_strings)?;
+                for rel in read_relationships(archive, &path)? {
+                    if rel.type_.ends_with("/comments") {
+                        if let Some(xml) = read_zip_text(archive, &rel.target)? {
+                            sheet.comments.extend(parse_comments(&xml)?);
+                        }
+                    }
+                }
+                sheets.push(sheet);
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("Failed to parse {}: {}", workbook_path, e)),
+            _ => {}
+        }
+    }
+    Ok(sheets)
+}
+
+// 共有文字列のテーブル（ふりがな `<rPh>` は読みの重複になるので除く）
+fn parse_shared_strings(xml: &str) -> Result<Vec<String>, String> {
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut phonetic_depth = 0;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"rPh" => phonetic_depth += 1,
+                b"t" => in_text = phonetic_depth == 0,
+                _ => {}
+            },
+            Ok(Event::Empty(e)) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"si" => strings.push(std::mem::take(&mut current)),
+                b"rPh" => phonetic_depth -= 1,
+                b"t" => in_text = false,
+                _ => {}
+            },
+            Ok(Event::Text(t)) if in_text => {
+                current.push_str(&t.unescape().map_err(|e| format!("Failed to parse shared strings: {}", e))?);
+            }
+            Ok(Event::CData(t)) if in_text => current.push_str(&String::from_utf8_lossy(&t)),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("Failed to parse shared strings: {}", e)),
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+// ワークシートのセルの値（共有文字列・インライン文字列・数式の結果・数値・真偽値）
+fn parse_worksheet(xml: &str, shared_strings: &[String]) -> Result<Vec<Cell>, String> {
+    let mut cells = Vec::new();
+    let mut row = 0;
+    let mut column = 0;
+    let mut cell_type = String::new();
+    let mut value = String::new();
+    let mut in_value = false;
+    let mut phonetic_depth = 0;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"row" => {
+                    row = attribute(&e, "r").and_then(|r| r.parse().ok()).unwrap_or(row + 1);
+                    column = 0;
+                }
+                b"c" => {
+                    (row, column) = attribute(&e, "r")
+                        .and_then(|r| parse_cell_reference(&r))
+                        .unwrap_or((row, column + 1));
+                    cell_type = attribute(&e, "t").unwrap_or_default();
+                    value.clear();
+                }
+                b"rPh" => phonetic_depth += 1,
+                b"v" => in_value = true,
+                b"t" => in_value = phonetic_depth == 0,
+                _ => {}
+            },
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"row" => row = attribute(&e, "r").and_then(|r| r.parse().ok()).unwrap_or(row + 1),
+                b"c" => {
+                    (row, column) = attribute(&e, "r")
+                        .and_then(|r| parse_cell_reference(&r))
+                        .unwrap_or((row, column + 1));
+                }
+                _ => {}
+            },
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"c" => {
+                    let text = match cell_type.as_str() {
+                        "s" => value.trim().parse::<usize>().ok()
+                            .and_then(|index| shared_strings.get(index))
+                            .cloned()
+                            .unwrap_or_default(),
+                        "b" => if value.trim() == "1" { "TRUE".to_string() } else { "FALSE".to_string() },
+                        // エラー値（#N/A など）は個人情報を含まない
+                        "e" => String::new(),
+                        _ => std::mem::take(&mut value),
+                    };
+                    if !text.is_empty() {
+                        cells.push(Cell { row, column, text });
+                    }
+                }
+                b"rPh" => phonetic_depth -= 1,
+                b"v" | b"t" => in_value = false,
+                _ => {}
+            },
+            Ok(Event::Text(t)) if in_value => {
+                value.push_str(&t.unescape().map_err(|e| format!("Failed to parse worksheet: {}", e))?);
+            }
+            Ok(Event::CData(t)) if in_value => value.push_str(&String::from_utf8_lossy(&t)),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("Failed to parse worksheet: {}", e)),
+            _ => {}
+        }
+    }
+    Ok(cells)
+}
+
+// セルのコメント（`xl/comments1.xml` など）
+fn parse_comments(xml: &str) -> Result<Vec<Cell>, String> {
+    let mut comments = Vec::new();
+    let mut position = None;
+    let mut text = String::new();
+    let mut in_text = false;
+    let mut phonetic_depth = 0;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"comment" => {
+                    position = attribute(&e, "ref").and_then(|r| parse_cell_reference(&r));
+                    text.clear();
+                }
+                b"rPh" => phonetic_depth += 1,
+                b"t" => in_text = position.is_some() && phonetic_depth == 0,
+                _ => {}
+            },
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"comment" => {
+                    if let Some((row, column)) = position.take() {
+                        if !text.trim().is_empty() {
+                            comments.push(Cell { row, column, text: std::mem::take(&mut text) });
+                        }
+                    }
+                }
+                b"rPh" => phonetic_depth -= 1,
+                b"t" => in_text = false,
+                _ => {}
+            },
+            Ok(Event::Text(t)) if in_text => {
+                text.push_str(&t.unescape().map_err(|e| format!("Failed to parse comments: {}", e))?);
+            }
+            Ok(Event::CData(t)) if in_text => text.push_str(&String::from_utf8_lossy(&t)),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("Failed to parse comments: {}", e)),
+            _ => {}
+        }
+    }
+    Ok(comments)
+}
+
+// ---- ods ----
+
+// 繰り返し属性の上限。行・列は表計算ソフトのシートの大きさ、連続する空白（text:s）は少数に抑える
+const ODS_MAX_ROWS: u32 = 1_048_576;
+const ODS_MAX_COLUMNS: u32 = 16_384;
+const ODS_MAX_SPACES: u32 = 64;
+
+// `content.xml` の各テーブルを読む。繰り返し属性（number-rows-repeated など）の付いた値のあるセルは最初の位置に1回だけ出力する
+fn read_ods(xml: &str) -> Result<Vec<Sheet>, String> {
+    let mut sheets: Vec<Sheet> = Vec::new();
+    let mut row = 0u32;
+    let mut rows_repeated = 1u32;
+    let mut column = 0u32;
+    let mut columns_repeated = 1u32;
+    let mut cell: Option<String> = None;
+    let mut annotation: Option<String> = None;
+    let mut paragraph_depth = 0;
+    let mut metadata_depth = 0;
+
+    let repeated = |e: &quick_xml::events::BytesStart, name: &str, max: u32| {
+        attribute(e, name).and_then(|n| n.parse::<u32>().ok()).unwrap_or(1).clamp(1, max)
+    };
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        let event = reader.read_event()
+            .map_err(|e| format!("Failed to parse content.xml: {}", e))?;
+        match event {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"table" => {
+                    sheets.push(Sheet::new(attribute(&e, "name").unwrap_or_default()));
+                    row = 0;
+                    rows_repeated = 1;
+                }
+                b"table-row" => {
+                    row = row.saturating_add(rows_repeated);
+                    rows_repeated = repeated(&e, "number-rows-repeated", ODS_MAX_ROWS);
+                    column = 0;
+                    columns_repeated = 1;
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    column = column.saturating_add(columns_repeated);
+                    columns_repeated = repeated(&e, "number-columns-repeated", ODS_MAX_COLUMNS);
+                    cell = Some(String::new());
+                }
+                b"annotation" if cell.is_some() => annotation = Some(String::new()),
+                // コメントの作成者・日時は本文に含めない
+                b"creator" | b"date" if annotation.is_some() => metadata_depth += 1,
+                b"p" | b"h" => {
+                    let target = annotation.as_mut().or(cell.as_mut());
+                    if let Some(target) = target {
+                        if !target.is_empty() {
+                            target.push('\n');
+                        }
+                    }
+                    paragraph_depth += 1;
+                }
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"table-row" => {
+                    row = row.saturating_add(rows_repeated);
+                    rows_repeated = repeated(&e, "number-rows-repeated", ODS_MAX_ROWS);
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    column = column.saturating_add(columns_repeated);
+                    columns_repeated = repeated(&e, "number-columns-repeated", ODS_MAX_COLUMNS);
+                }
+                b"s" | b"tab" | b"line-break" if paragraph_depth > 0 => {
+                    let count = if e.local_name().as_ref() == b"s" { repeated(&e, "c", ODS_MAX_SPACES) } else { 1 };
+                    if let Some(target) = annotation.as_mut().or(cell.as_mut()) {
+                        target.extend(std::iter::repeat_n(' ', count as usize));
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"table-cell" | b"covered-table-cell" => {
+                    if let (Some(text), Some(sheet)) = (cell.take(), sheets.last_mut()) {
+                        if !text.trim().is_empty() {
+                            sheet.cells.push(Cell { row, column, text });
+                        }
+                    }
+                }
+                b"annotation" => {
+                    if let (Some(text), Some(sheet)) = (annotation.take(), sheets.last_mut()) {
+                        if !text.trim().is_empty() {
+                            sheet.comments.push(Cell { row, column, text });
+                        }
+                    }
+                }
+                b"creator" | b"date" if metadata_depth > 0 => metadata_depth -= 1,
+                b"p" | b"h" if paragraph_depth > 0 => paragraph_depth -= 1,
+                _ => {}
+            },
+            Event::Text(t) if paragraph_depth > 0 && metadata_depth == 0 => {
+                let text = t.unescape().map_err(|e| format!("Failed to parse content.xml: {}", e))?;
+                if let Some(target) = annotation.as_mut().or(cell.as_mut()) {
+                    target.push_str(&text);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sheets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn write_zip(entries: &[(&str, &str)]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    fn labeled_values(extracted: &ExtractedText) -> Vec<(String, String)> {
+        let lines: Vec<&str> = extracted.text.lines().collect();
+        extracted.locations.iter().enumerate()
+            .map(|(i, location)| {
+                let line = lines[location.line as usize - 1];
+                let end = extracted.locations.get(i + 1)
+                    .filter(|next| next.line == location.line)
+                    .map_or(line.len(), |next| next.start as usize);
+                (location.label.clone(), line[location.start as usize..end].trim_end_matches('\t').to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_xlsx_cells_hidden_sheets_and_comments() {
+        let mut archive = write_zip(&[
+            ("_rels/.rels", r#"<Relationships><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#),
+            ("xl/workbook.xml", r#"<workbook xmlns:r="r"><sheets>
+                <sheet name="Customers" sheetId="1" r:id="rId1"/>
+                <sheet name="Old Data" sheetId="2" state="hidden" r:id="rId2"/>
+            </sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships>
+                <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+                <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="/xl/worksheets/sheet2.xml"/>
+                <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings" Target="sharedStrings.xml"/>
+            </Relationships>"#),
+            ("xl/sharedStrings.xml", r#"<sst><si><t>氏名</t></si><si><r><t>山田</t></r><r><t> 太郎</t></r><rPh><t>ヤマダ</t></rPh></si><si><t>a&amp;b@example.com</t></si></sst>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet><sheetData>
+                <row r="1"><c r="A1" t="s"><v>0</v></c></row>
+                <row r="12"><c r="B12" t="s"><v>1</v></c><c r="C12" t="inlineStr"><is><t>090-1234-5678</t></is></c><c r="D12"><v>42</v></c></row>
+            </sheetData></worksheet>"#),
+            ("xl/worksheets/_rels/sheet1.xml.rels", r#"<Relationships><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments1.xml"/></Relationships>"#),
+            ("xl/comments1.xml", r#"<comments><authors><author>A</author></authors><commentList><comment ref="C12" authorId="0"><text><r><t>本人確認済み</t></r></text></comment></commentList></comments>"#),
+            ("xl/worksheets/sheet2.xml", r#"<worksheet><sheetData><row><c t="s"><v>2</v></c><c t="str"><f>A1</f><v>x</v></c></row></sheetData></worksheet>"#),
+        ]);
+
+        let extracted = render(&read_sheets(&mut archive).unwrap());
+        assert_eq!(labeled_values(&extracted), vec![
+            ("Customers!A1".to_string(), "氏名".to_string()),
+            ("Customers!B12".to_string(), "山田 太郎".to_string()),
+            ("Customers!C12".to_string(), "090-1234-5678".to_string()),
+            ("Customers!D12".to_string(), "42".to_string()),
+            ("Customers!C12 (comment)".to_string(), "本人確認済み".to_string()),
+            ("'Old Data'!A1".to_string(), "a&b@example.com".to_string()),
+            ("'Old Data'!B1".to_string(), "x".to_string()),
+        ]);
+        assert_eq!(ExtractedText::location_at(&extracted.locations, 2, 16).map(|location| location.label.as_str()), Some("Customers!C12"));
+    }
+
+    #[test]
+    fn test_ods_repeated_cells_and_annotations() {
+        let mut archive = write_zip(&[
+            ("mimetype", "application/vnd.oasis.opendocument.spreadsheet"),
+            ("content.xml", r#"<office:document-content xmlns:office="o" xmlns:table="t" xmlns:text="x" xmlns:dc="d"><office:body><office:spreadsheet>
+                <table:table table:name="顧客">
+                    <table:table-row table:number-rows-repeated="2"><table:table-cell table:number-columns-repeated="3"/></table:table-row>
+                    <table:table-row>
+                        <table:table-cell table:number-columns-repeated="2"/>
+                        <table:table-cell><text:p>東京都<text:s text:c="2"/>千代田区</text:p>
+                            <office:annotation><dc:creator>担当者</dc:creator><dc:date>2024-01-01</dc:date><text:p>要確認</text:p></office:annotation>
+                        </table:table-cell>
+                    </table:table-row>
+                </table:table>
+            </office:spreadsheet></office:body></office:document-content>"#),
+        ]);
+
+        let extracted = render(&read_sheets(&mut archive).unwrap());
+        assert_eq!(labeled_values(&extracted), vec![
+            ("顧客!C3".to_string(), "東京都  千代田区".to_string()),
+            ("顧客!C3 (comment)".to_string(), "要確認".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_ods_clamps_huge_repeat_counts() {
+        let mut archive = write_zip(&[
+            ("mimetype", "application/vnd.oasis.opendocument.spreadsheet"),
+            ("content.xml", r#"<office:document-content xmlns:office="o" xmlns:table="t" xmlns:text="x"><office:body><office:spreadsheet>
+                <table:table table:name="S">
+                    <table:table-row table:number-rows-repeated="4294967295"><table:table-cell table:number-columns-repeated="4294967295"/></table:table-row>
+                    <table:table-row>
+                        <table:table-cell table:number-columns-repeated="4294967295"/>
+                        <table:table-cell><text:p>a<text:s text:c="4294967295"/>b</text:p></table:table-cell>
+                    </table:table-row>
+                </table:table>
+            </office:spreadsheet></office:body></office:document-content>"#),
+        ]);
+
+        let sheets = read_sheets(&mut archive).unwrap();
+        let cell = &sheets[0].cells[0];
+        assert_eq!((cell.row, cell.column), (ODS_MAX_ROWS + 1, ODS_MAX_COLUMNS + 1));
+        assert_eq!(cell.text, format!("a{}b", " ".repeat(ODS_MAX_SPACES as usize)));
+    }
+
+    #[test]
+    fn test_cell_reference_round_trip() {
+        assert_eq!(parse_cell_reference("C12"), Some((12, 3)));
+        assert_eq!(parse_cell_reference("AA1"), Some((1, 27)));
+        assert_eq!(column_name(27), "AA");
+        assert_eq!(column_name(702), "ZZ");
+    }
+}
@@ -1,15 +1,15 @@
-use crate::domain::TextExtractor;
+use crate::domain::{ExtractedText, TextExtractor};
 use crate::infrastructure::error::AppError;
 use std::error::Error as StdError;
 use std::path::Path;
 use std::fs;
+use std::io;
 
 pub struct PlainTextExtractor;
 
 impl TextExtractor for PlainTextExtractor {
-    fn supports(&self, file_type: &str) -> bool {
-        let file_type = file_type.to_lowercase();
-        matches!(file_type.as_str(), "txt" | "md" | "csv" | "json" | "xml" | "html" | "log")
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt", "md", "csv", "json", "xml", "html", "log"]
     }
     
     fn extract(&self, file_path: &Path) -> Result<String, Box<dyn StdError + Send + Sync>> {
@@ -17,4 +17,9 @@ impl TextExtractor for PlainTextExtractor {
             .map_err(|e| AppError::IoError(e).into())
     }
     
+    fn extract_bytes_with_locations(&self, data: &[u8]) -> Result<ExtractedText, Box<dyn StdError + Send + Sync>> {
+        let text = String::from_utf8(data.to_vec())
+            .map_err(|e| AppError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+        Ok(ExtractedText::plain(text))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,244 @@
+use crate::infrastructure::error::AppError;
+use crate::utils::config::ArchiveConfig;
+use flate2::read::GzDecoder;
+use std::io::{self, Cursor, Read};
+use zip::ZipArchive;
+
+// これより小さいファイルは圧縮率が高くても許可する（空白だけのファイルなどは正常でも圧縮率が高い）
+const RATIO_CHECK_MIN_BYTES: u64 = 1024 * 1024;
+
+/// アーカイブの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// ファイル名の拡張子からアーカイブの形式を判定する
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// アーカイブから取り出したファイル
+pub struct ArchiveEntry {
+    /// アーカイブ内のパス
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// 展開できる残りの量。入れ子のアーカイブを含めて1つのアーカイブ全体で共有する
+pub struct ExtractionBudget {
+    remaining_entries: usize,
+    remaining_bytes: u64,
+    max_entry_bytes: u64,
+    max_ratio: u64,
+}
+
+impl ExtractionBudget {
+    pub fn new(config: &ArchiveConfig) -> Self {
+        ExtractionBudget {
+            remaining_entries: config.max_entries,
+            remaining_bytes: config.max_total_size_mb * 1024 * 1024,
+            max_entry_bytes: config.max_entry_size_mb * 1024 * 1024,
+            max_ratio: config.max_compression_ratio,
+        }
+    }
+
+    /// アーカイブを読み込む前に、ファイルのサイズが展開量の合計の上限を超えていないか確認する
+    pub fn check_archive_size(&self, name: &str, size: u64) -> Result<(), AppError> {
+        if size > self.remaining_bytes {
+            return Err(AppError::ArchiveError(format!("{} exceeds the total size limit", name)));
+        }
+        Ok(())
+    }
+
+    // 圧縮後のサイズから、圧縮率の上限を超えない展開後のサイズの上限を求める
+    fn ratio_limit(&self, compressed_bytes: u64) -> u64 {
+        compressed_bytes.saturating_mul(self.max_ratio).max(RATIO_CHECK_MIN_BYTES)
+    }
+
+    // ファイルを1つ取り出す。宣言されたサイズではなく実際に読んだ量で上限を確認する
+    fn take<R: Read>(&mut self, name: &str, reader: R, ratio_limit: u64) -> Result<Vec<u8>, AppError> {
+        if self.remaining_entries == 0 {
+            return Err(AppError::ArchiveError("Too many files in archive".to_string()));
+        }
+        self.remaining_entries -= 1;
+
+        let limit = self.max_entry_bytes.min(self.remaining_bytes).min(ratio_limit);
+        let mut data = Vec::new();
+        reader.take(limit + 1).read_to_end(&mut data)
+            .map_err(|e| AppError::ArchiveError(format!("Failed to read {}: {}", name, e)))?;
+
+        let size = data.len() as u64;
+        if size > limit {
+            let reason = if size > ratio_limit {
+                "compression ratio limit"
+            } else if size > self.max_entry_bytes {
+                "file size limit"
+            } else {
+                "total size limit"
+            };
+            return Err(AppError::ArchiveError(format!("{} exceeds the {} (possible zip bomb)", name, reason)));
+        }
+        self.remaining_bytes -= size;
+        Ok(data)
+    }
+}
+
+/// アーカイブの中のファイルのうち、`wanted` が名前で選んだものだけをメモリ上に取り出す（ディレクトリやリンクは除く）
+///
+/// 選ばれなかったファイルは読まないので、展開量の上限にも数えない。
+pub fn read_entries(
+    kind: ArchiveKind,
+    data: &[u8],
+    budget: &mut ExtractionBudget,
+    wanted: &dyn Fn(&str) -> bool,
+) -> Result<Vec<ArchiveEntry>, AppError> {
+    match kind {
+        ArchiveKind::Zip => read_zip(data, budget, wanted),
+        ArchiveKind::Tar => read_tar(data, budget, wanted),
+        ArchiveKind::TarGz => {
+            // gzip は全体で1つの圧縮ストリームなので、展開後の合計に圧縮率の上限をかける
+            let ratio_limit = budget.ratio_limit(data.len() as u64);
+            read_tar(LimitedReader::new(GzDecoder::new(data), ratio_limit), budget, wanted)
+        }
+    }
+}
+
+fn read_zip(data: &[u8], budget: &mut ExtractionBudget, wanted: &dyn Fn(&str) -> bool) -> Result<Vec<ArchiveEntry>, AppError> {
+    let mut archive = ZipArchive::new(Cursor::new(data))
+        .map_err(|e| AppError::ArchiveError(format!("Failed to open zip archive: {}", e)))?;
+
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        let file = match archive.by_index(index) {
+            Ok(file) => file,
+            // 暗号化されたファイルなどは読めないので飛ばす
+            Err(e) => {
+                eprintln!("Skipping zip entry #{}: {}", index, e);
+                continue;
+            }
+        };
+        if file.is_dir() || !wanted(file.name()) {
+            continue;
+        }
+
+        let name = file.name().to_string();
+        let ratio_limit = budget.ratio_limit(file.compressed_size());
+        let data = budget.take(&name, file, ratio_limit)?;
+        entries.push(ArchiveEntry { name, data });
+    }
+    Ok(entries)
+}
+
+fn read_tar<R: Read>(reader: R, budget: &mut ExtractionBudget, wanted: &dyn Fn(&str) -> bool) -> Result<Vec<ArchiveEntry>, AppError> {
+    let mut archive = tar::Archive::new(reader);
+    let tar_error = |e: io::Error| AppError::ArchiveError(format!("Failed to read tar archive: {}", e));
+
+    let mut entries = Vec::new();
+    for entry in archive.entries().map_err(tar_error)? {
+        let entry = entry.map_err(tar_error)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let name = entry.path().map_err(tar_error)?.to_string_lossy().into_owned();
+        if !wanted(&name) {
+            continue;
+        }
+        // tar は圧縮しないので、圧縮率の上限はかけない
+        let data = budget.take(&name, entry, u64::MAX)?;
+        entries.push(ArchiveEntry { name, data });
+    }
+    Ok(entries)
+}
+
+// 読み込める量に上限のあるリーダー（上限を超えるとエラーを返す）
+struct LimitedReader<R> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R> LimitedReader<R> {
+    fn new(inner: R, limit: u64) -> Self {
+        LimitedReader { inner, remaining: limit }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.remaining = self.remaining.checked_sub(read as u64).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "compression ratio limit exceeded (possible zip bomb)")
+        })?;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn zip_with(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_read_entries_and_reject_zip_bombs() {
+        let config = ArchiveConfig::default();
+
+        // tar.gz
+        let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        tar.append_data(&mut header, "hr/list.txt", &b"hello"[..]).unwrap();
+        let tar_gz = tar.into_inner().unwrap().finish().unwrap();
+        let entries = read_entries(ArchiveKind::TarGz, &tar_gz, &mut ExtractionBudget::new(&config), &|_| true).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].name.as_str(), entries[0].data.as_slice()), ("hr/list.txt", &b"hello"[..]));
+
+        // 圧縮率が上限を超えるファイル
+        let bomb = zip_with(&[("zeros.txt", &vec![0u8; 4 * 1024 * 1024])]);
+        let error = read_entries(ArchiveKind::Zip, &bomb, &mut ExtractionBudget::new(&config), &|_| true).err().unwrap();
+        assert!(error.to_string().contains("compression ratio"), "{}", error);
+
+        // 選ばれなかったファイルは読まず、上限にも数えない
+        let mut budget = ExtractionBudget::new(&ArchiveConfig { max_total_size_mb: 1, ..ArchiveConfig::default() });
+        let images = zip_with(&[("photo.jpg", &vec![7u8; 2 * 1024 * 1024]), ("notes.txt", b"hello")]);
+        let entries = read_entries(ArchiveKind::Zip, &images, &mut budget, &|name| name.ends_with(".txt")).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "notes.txt");
+
+        // 合計サイズとファイル数の上限は入れ子を含めて共有される
+        let small = zip_with(&[("a.txt", b"a"), ("b.txt", b"b")]);
+        let mut budget = ExtractionBudget::new(&ArchiveConfig { max_entries: 3, ..ArchiveConfig::default() });
+        assert_eq!(read_entries(ArchiveKind::Zip, &small, &mut budget, &|_| true).unwrap().len(), 2);
+        assert!(read_entries(ArchiveKind::Zip, &small, &mut budget, &|_| true).is_err());
+
+        assert_eq!(ArchiveKind::from_name("Bundle.TGZ"), Some(ArchiveKind::TarGz));
+        assert_eq!(ArchiveKind::from_name("report.gz"), None);
+    }
+}
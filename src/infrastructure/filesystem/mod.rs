@@ -1,3 +1,4 @@
+pub mod archive;
 pub mod scanner;
 
 pub use scanner::FileSystemScanner;
\ No newline at end of file
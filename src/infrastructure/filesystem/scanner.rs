@@ -2,28 +2,147 @@ use crate::domain::models::FileInfo;
 use crate::domain::service::ScanService;
 use crate::infrastructure::error::AppError;
 use crate::infrastructure::extractors::ExtractorManager;
+use crate::infrastructure::filesystem::archive::{read_entries, ArchiveKind, ExtractionBudget};
+use crate::utils::config::ArchiveConfig;
 // use crate::utils::progress::ProgressBar; // ProgressBarのuse宣言を追加
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
 use std::error::Error as StdError;
+use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+
+// .gitignore と同じ書式で、スキャンから除外するファイルを指定する
+const SECSCAN_IGNORE_FILE: &str = ".secscanignore";
+
+/// ディレクトリを走査する際の対象ファイルの絞り込み
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// 指定した場合、いずれかに一致するファイルだけをスキャンする（glob）
+    pub include: Vec<String>,
+    /// 一致するファイル・ディレクトリを除外する（glob）
+    pub exclude: Vec<String>,
+    /// 走査する深さの上限（指定したディレクトリ直下が1）
+    pub max_depth: Option<usize>,
+    /// 隠しファイル・隠しディレクトリもスキャンする（.git は常に除外）
+    pub hidden: bool,
+}
 
 pub struct FileSystemScanner {
     extractor_manager: ExtractorManager,
+    walk_options: WalkOptions,
+    archive_config: ArchiveConfig,
 }
 
 impl FileSystemScanner {
     pub fn new(extractor_manager: ExtractorManager) -> Self {
-        FileSystemScanner { extractor_manager }
+        FileSystemScanner {
+            extractor_manager,
+            walk_options: WalkOptions::default(),
+            archive_config: ArchiveConfig::default(),
+        }
+    }
+    
+    /// ディレクトリ走査の絞り込みを設定する
+    pub fn set_walk_options(&mut self, walk_options: WalkOptions) {
+        self.walk_options = walk_options;
+    }
+    
+    /// アーカイブの中のファイルをスキャンするかどうかと展開量の上限を設定する
+    pub fn set_archive_config(&mut self, archive_config: ArchiveConfig) {
+        self.archive_config = archive_config;
+    }
+    
+    // --exclude のglobから走査の上書きルールを作る
+    // （--include を上書きルールにすると .gitignore などで除外されたファイルも対象に戻るため、走査後に絞り込む）
+    fn build_overrides(&self, root: &Path) -> Result<Override, AppError> {
+        let mut builder = OverrideBuilder::new(root);
+        for glob in &self.walk_options.exclude {
+            builder.add(&format!("!{}", glob))
+                .map_err(|e| AppError::ConfigError(format!("Invalid --exclude glob '{}': {}", glob, e)))?;
+        }
+        builder.build()
+            .map_err(|e| AppError::ConfigError(format!("Invalid --exclude globs: {}", e)))
+    }
+
+    // --include のglobに一致するファイルを選ぶ（指定がなければNone）
+    fn build_include_filter(&self, root: &Path) -> Result<Option<Override>, AppError> {
+        if self.walk_options.include.is_empty() {
+            return Ok(None);
+        }
+        let mut builder = OverrideBuilder::new(root);
+        for glob in &self.walk_options.include {
+            builder.add(glob)
+                .map_err(|e| AppError::ConfigError(format!("Invalid --include glob '{}': {}", glob, e)))?;
+        }
+        builder.build()
+            .map(Some)
+            .map_err(|e| AppError::ConfigError(format!("Invalid --include globs: {}", e)))
     }
 
-    // ファイル拡張子が対応しているかどうかを確認
+    // ファイル拡張子が対応しているかどうかを確認（登録済みの抽出器と設定の対象拡張子による）
     fn is_supported_extension(&self, path: &Path) -> bool {
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            // 対応する拡張子リスト
-            let supported_extensions = ["txt", "md", "csv", "pdf", "docx"];
-            return supported_extensions.contains(&ext.to_lowercase().as_str());
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| self.extractor_manager.supports_extension(ext))
+    }
+
+    // スキャンするアーカイブの形式（アーカイブのスキャンが無効な場合はNone）
+    fn archive_kind(&self, name: &str) -> Option<ArchiveKind> {
+        if !self.archive_config.enabled {
+            return None;
         }
-        false
+        ArchiveKind::from_name(name)
+    }
+
+    // アーカイブの中の対応するファイルを `bundle.zip!/hr/list.docx` のような仮想パスで抽出する。
+    // 読めないファイルや入れ子のアーカイブは警告を出して飛ばすが、展開量の上限を超えた場合はアーカイブ全体をエラーにする
+    fn process_archive(
+        &self,
+        virtual_path: &str,
+        kind: ArchiveKind,
+        data: &[u8],
+        depth: usize,
+        budget: &mut ExtractionBudget,
+        file_infos: &mut Vec<FileInfo>,
+    ) -> Result<(), AppError> {
+        // 対応する拡張子のファイルと入れ子のアーカイブだけを読む
+        let wanted = |name: &str| {
+            if self.archive_kind(name).is_none() {
+                return self.is_supported_extension(Path::new(name));
+            }
+            if depth >= self.archive_config.max_depth {
+                eprintln!(
+                    "Skipping {}!/{}: nested archive depth limit ({}) reached",
+                    virtual_path, name.trim_start_matches('/'), self.archive_config.max_depth
+                );
+                return false;
+            }
+            true
+        };
+
+        for entry in read_entries(kind, data, budget, &wanted)? {
+            let path = format!("{}!/{}", virtual_path, entry.name.trim_start_matches('/'));
+
+            if let Some(nested_kind) = self.archive_kind(&entry.name) {
+                if let Err(e) = self.process_archive(&path, nested_kind, &entry.data, depth + 1, budget, file_infos) {
+                    eprintln!("Skipping {}: {}", path, e);
+                }
+                continue;
+            }
+
+            let Some(extension) = Path::new(&entry.name).extension().and_then(|ext| ext.to_str()) else {
+                continue;
+            };
+            match self.extractor_manager.extract_bytes_with_locations(extension, &entry.data) {
+                Ok(extracted) => file_infos.push(FileInfo {
+                    path,
+                    content: extracted.text,
+                    locations: extracted.locations,
+                }),
+                Err(e) => eprintln!("Skipping {}: {}", path, e),
+            }
+        }
+        Ok(())
     }
 }
 
@@ -47,25 +166,39 @@ impl ScanService for FileSystemScanner {
 
         if path.is_file() {
             // 単一ファイルの場合
-            if self.is_supported_extension(path) {
+            if self.is_supported_extension(path) || self.archive_kind(&path.to_string_lossy()).is_some() {
                 files_to_scan.push(path.to_path_buf());
             }
         } else if path.is_dir() {
-            // ディレクトリの場合
-            let walker = if recursive {
-                WalkDir::new(path).follow_links(true).into_iter()
-            } else {
-                // 再帰的でない場合は深さ1に制限
-                WalkDir::new(path)
-                    .max_depth(1)
-                    .follow_links(true)
-                    .into_iter()
+            // ディレクトリの場合（.gitignore / .ignore / .secscanignore に従い、シンボリックリンクのループは検出して飛ばす）
+            let max_depth = match (recursive, self.walk_options.max_depth) {
+                (false, _) => Some(1), // 再帰的でない場合は深さ1に制限
+                (true, max_depth) => max_depth,
             };
+            let walker = WalkBuilder::new(path)
+                .follow_links(true)
+                .hidden(!self.walk_options.hidden)
+                .require_git(false)
+                .add_custom_ignore_filename(SECSCAN_IGNORE_FILE)
+                .overrides(self.build_overrides(path)?)
+                .max_depth(max_depth)
+                .filter_entry(|entry| entry.file_name() != ".git")
+                .build();
+            let include = self.build_include_filter(path)?;
 
-            for entry in walker.filter_map(|e| e.ok()) {
-                let entry_path = entry.path();
-                if entry_path.is_file() && self.is_supported_extension(entry_path) {
-                    files_to_scan.push(entry_path.to_path_buf());
+            for entry in walker {
+                match entry {
+                    Ok(entry) => {
+                        let entry_path = entry.path();
+                        let supported = self.is_supported_extension(entry_path)
+                            || self.archive_kind(&entry_path.to_string_lossy()).is_some();
+                        let included = include.as_ref()
+                            .is_none_or(|include| include.matched(entry_path, false).is_whitelist());
+                        if entry_path.is_file() && supported && included {
+                            files_to_scan.push(entry_path.to_path_buf());
+                        }
+                    }
+                    Err(e) => eprintln!("Skipping path: {}", e),
                 }
             }
         }
@@ -98,7 +231,7 @@ impl ScanService for FileSystemScanner {
         // let progress_bar = ProgressBar::new(total_files);
 
         // ファイル拡張子を取得して抽出マネージャーでテキスト抽出
-        let content = self.extractor_manager.extract(file_path)?;
+        let extracted = self.extractor_manager.extract_with_locations(file_path)?;
 
         // 進捗バーを更新
         // progress_bar.update();
@@ -106,7 +239,116 @@ impl ScanService for FileSystemScanner {
 
         Ok(FileInfo {
             path: file_path.to_string_lossy().to_string(),
-            content,
+            content: extracted.text,
+            locations: extracted.locations,
         })
     }
+
+    fn process_path(&self, file_path: &Path) -> Result<Vec<FileInfo>, Box<dyn StdError + Send + Sync>> {
+        let path = file_path.to_string_lossy().to_string();
+        let Some(kind) = self.archive_kind(&path) else {
+            return Ok(vec![self.process_file(file_path)?]);
+        };
+
+        // アーカイブはメモリ上で展開する（入れ子のアーカイブも含めて展開量の上限を共有する）
+        let mut budget = ExtractionBudget::new(&self.archive_config);
+        let size = fs::metadata(file_path).map_err(AppError::IoError)?.len();
+        budget.check_archive_size(&path, size)?;
+        let data = fs::read(file_path).map_err(AppError::IoError)?;
+        let mut file_infos = Vec::new();
+        self.process_archive(&path, kind, &data, 1, &mut budget, &mut file_infos)?;
+        Ok(file_infos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::extractors::PlainTextExtractor;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_scan_path_respects_ignore_files_and_globs() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["src", "node_modules/pkg", "secret", ".hidden", "deep/a/b"] {
+            fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        for file in [
+            "src/main.txt", "src/notes.md", "node_modules/pkg/readme.txt", "secret/keys.txt",
+            ".hidden/data.txt", "deep/a/b/far.txt", "skip.csv",
+        ] {
+            fs::write(root.join(file), "x").unwrap();
+        }
+        fs::write(root.join(".gitignore"), "node_modules/\n").unwrap();
+        fs::write(root.join(".secscanignore"), "secret/\n*.draft.txt\n").unwrap();
+        fs::write(root.join("src/memo.draft.txt"), "x").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root, root.join("src/loop")).unwrap();
+
+        let mut extractor_manager = ExtractorManager::new();
+        extractor_manager.register(PlainTextExtractor);
+        let mut scanner = FileSystemScanner::new(extractor_manager);
+        scanner.set_walk_options(WalkOptions {
+            include: vec!["*.txt".to_string(), "*.md".to_string()],
+            exclude: vec!["notes.md".to_string()],
+            max_depth: Some(3),
+            hidden: false,
+        });
+
+        let mut files: Vec<String> = scanner.scan_path(root, true).unwrap()
+            .iter()
+            .map(|path| path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        files.sort();
+        // --include に一致しても .secscanignore で除外されたファイルは対象に戻らない
+        assert_eq!(files, vec!["src/main.txt"]);
+    }
+
+    #[test]
+    fn test_process_path_reads_nested_archives_with_virtual_paths() {
+        use std::io::{Cursor, Write};
+        use zip::write::FileOptions;
+        use zip::ZipWriter;
+
+        let mut inner = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(19);
+        header.set_mode(0o644);
+        inner.append_data(&mut header, "deep.txt", &b"mail: b@example.com"[..]).unwrap();
+        let inner = inner.into_inner().unwrap().finish().unwrap();
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [("hr/list.txt", &b"mail: a@example.com"[..]), ("logo.png", b"png"), ("old/inner.tar.gz", &inner)] {
+            writer.start_file(name, FileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        let dir = tempdir().unwrap();
+        let bundle = dir.path().join("bundle.zip");
+        fs::write(&bundle, writer.finish().unwrap().into_inner()).unwrap();
+
+        let mut extractor_manager = ExtractorManager::new();
+        extractor_manager.register(PlainTextExtractor);
+        let mut scanner = FileSystemScanner::new(extractor_manager);
+        assert_eq!(scanner.scan_path(dir.path(), true).unwrap(), vec![bundle.clone()]);
+
+        let bundle_path = bundle.to_string_lossy();
+        let infos = scanner.process_path(&bundle).unwrap();
+        let paths: Vec<&str> = infos.iter().map(|info| info.path.as_str()).collect();
+        assert_eq!(paths, vec![
+            format!("{}!/hr/list.txt", bundle_path),
+            format!("{}!/old/inner.tar.gz!/deep.txt", bundle_path),
+        ]);
+        assert_eq!(infos[1].content, "mail: b@example.com");
+
+        // 入れ子の深さの上限を超えるアーカイブは読まない
+        scanner.set_archive_config(ArchiveConfig { max_depth: 1, ..ArchiveConfig::default() });
+        assert_eq!(scanner.process_path(&bundle).unwrap().len(), 1);
+
+        // 展開量の合計の上限より大きいアーカイブは読み込む前に拒否する
+        scanner.set_archive_config(ArchiveConfig { max_total_size_mb: 0, ..ArchiveConfig::default() });
+        let err = scanner.process_path(&bundle).unwrap_err();
+        assert!(err.to_string().contains("total size limit"));
+    }
 }
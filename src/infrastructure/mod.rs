@@ -7,10 +7,12 @@ pub mod repositories;
 #[allow(unused_imports)]
 pub use error::AppError;
 
-pub use api::OllamaClient;
+pub use api::{OllamaClient, OpenAiCompatibleClient};
 pub use extractors::{
     PdfExtractor, 
     DocxExtractor, 
+    SpreadsheetExtractor,
+    PresentationExtractor,
     PlainTextExtractor, 
     ExtractorManager
 };
@@ -19,5 +21,10 @@ pub use repositories::{
     ApiDetector,
     RegexDetector,
     HybridDetector,
-    JsonOutputFormatter
+    AddressDetector,
+    NameDetector,
+    SecretDetector,
+    EntropyDetector,
+    JsonOutputFormatter,
+    LlmVerifier
 };
\ No newline at end of file
@@ -0,0 +1,156 @@
+use crate::domain::PersonalInformationDetector;
+use crate::domain::models::{PersonalInformation, Severity};
+use async_trait::async_trait;
+use std::error::Error as StdError;
+use regex::Regex;
+
+const DETECTOR_NAME: &str = "Address Detector";
+
+// 確信度（郵便番号は 〒 記号付きのみを対象とするため高めにする）
+const ADDRESS_CONFIDENCE: f32 = 0.85;
+const POSTAL_CODE_CONFIDENCE: f32 = 0.9;
+
+// 都道府県の辞書
+const PREFECTURES: [&str; 47] = [
+    "北海道", "青森県", "岩手県", "宮城県", "秋田県", "山形県", "福島県",
+    "茨城県", "栃木県", "群馬県", "埼玉県", "千葉県", "東京都", "神奈川県",
+    "新潟県", "富山県", "石川県", "福井県", "山梨県", "長野県", "岐阜県",
+    "静岡県", "愛知県", "三重県", "滋賀県", "京都府", "大阪府", "兵庫県",
+    "奈良県", "和歌山県", "鳥取県", "島根県", "岡山県", "広島県", "山口県",
+    "徳島県", "香川県", "愛媛県", "高知県", "福岡県", "佐賀県", "長崎県",
+    "熊本県", "大分県", "宮崎県", "鹿児島県", "沖縄県",
+];
+
+// 郵便番号 (例: 〒123-4567)
+const POSTAL_CODE: &str = r"〒\s?\d{3}[-－ー]?\d{4}";
+
+// 住所の構成要素に使われる文字（空白・数字・区切り記号以外）
+const NAME_CHAR: &str = r"[^\s\d、。,，「」『』()（）:：]";
+
+// 番地などに使われる数字（算用数字・漢数字）
+const NUMBER: &str = r"[\d〇一二三四五六七八九十百千]+";
+
+/// 日本の住所と郵便番号を検出する検出器
+///
+/// 都道府県 + 市区町村 + 丁目/番地/号 の並びを1つの `address` として報告する。
+/// 直前に郵便番号があれば住所の範囲に含め、単独の郵便番号は `postal_code` として報告する。
+pub struct AddressDetector {
+    address_re: Regex,
+    postal_code_re: Regex,
+}
+
+impl AddressDetector {
+    pub fn new() -> Self {
+        let prefectures = PREFECTURES.join("|");
+        let address_pattern = format!(
+            concat!(
+                r"(?:{postal}\s*)?",
+                r"(?:{prefectures})",
+                // 市区町村（郡や政令指定都市の区も含む）
+                r"(?:{c}{{1,6}}?郡)?{c}{{1,8}}?[市区町村](?:{c}{{1,5}}?区)?",
+                // 町名
+                r"{c}{{0,10}}?",
+                // 丁目・番地・号（例: 1丁目2番3号, 三丁目5-1, 1-2-3）
+                r"(?:{n}(?:丁目|番地|番|号|[-－ー‐の]))+{n}?(?:号)?",
+            ),
+            postal = POSTAL_CODE,
+            prefectures = prefectures,
+            c = NAME_CHAR,
+            n = NUMBER,
+        );
+        
+        AddressDetector {
+            address_re: Regex::new(&address_pattern).expect("address pattern must compile"),
+            postal_code_re: Regex::new(POSTAL_CODE).expect("postal code pattern must compile"),
+        }
+    }
+    
+    fn detect_line(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
+        let mut address_spans = Vec::new();
+        
+        for mat in self.address_re.find_iter(line) {
+            // 末尾の区切り記号は住所に含めない
+            let value = mat.as_str().trim_end_matches(['-', '－', 'ー', '‐', 'の']);
+            let end = mat.start() + value.len();
+            address_spans.push((mat.start(), end));
+            results.push(PersonalInformation {
+                type_: "address".to_string(),
+                value: value.to_string(),
+                line: line_idx + 1,
+                start: mat.start() as u32,
+                end: end as u32,
+                confidence: ADDRESS_CONFIDENCE,
+                detector: DETECTOR_NAME.to_string(),
+                severity: Severity::for_type("address"),
+                location: None,
+                page: None,
+            });
+        }
+        
+        // 住所の一部として報告済みの郵便番号は除く
+        for mat in self.postal_code_re.find_iter(line) {
+            let inside_address = address_spans.iter()
+                .any(|&(start, end)| mat.start() >= start && mat.end() <= end);
+            if !inside_address {
+                results.push(PersonalInformation {
+                    type_: "postal_code".to_string(),
+                    value: mat.as_str().to_string(),
+                    line: line_idx + 1,
+                    start: mat.start() as u32,
+                    end: mat.end() as u32,
+                    confidence: POSTAL_CODE_CONFIDENCE,
+                    detector: DETECTOR_NAME.to_string(),
+                    severity: Severity::for_type("postal_code"),
+                    location: None,
+                    page: None,
+                });
+            }
+        }
+    }
+}
+
+impl Default for AddressDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PersonalInformationDetector for AddressDetector {
+    async fn detect(&self, text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
+        let mut personal_info = Vec::new();
+        
+        for (line_idx, line) in text.lines().enumerate() {
+            self.detect_line(line, line_idx as u32, &mut personal_info);
+        }
+        
+        Ok(personal_info)
+    }
+    
+    fn name(&self) -> &str {
+        DETECTOR_NAME
+    }
+    
+    fn supported_types(&self) -> Option<Vec<String>> {
+        Some(vec!["address".to_string(), "postal_code".to_string()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_full_address_is_one_finding() {
+        let detector = AddressDetector::new();
+        let text = "住所：〒100-0001 東京都千代田区千代田一丁目1番1号 までお送りください\n大阪府大阪市北区梅田3-1-1\n郵便番号 〒530-0001";
+        let results = detector.detect(text).await.unwrap();
+        
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].type_, "address");
+        assert_eq!(results[0].value, "〒100-0001 東京都千代田区千代田一丁目1番1号");
+        assert_eq!(results[1].value, "大阪府大阪市北区梅田3-1-1");
+        assert_eq!(results[2].type_, "postal_code");
+        assert_eq!(results[2].value, "〒530-0001");
+    }
+}
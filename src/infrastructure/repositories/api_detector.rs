@@ -1,11 +1,21 @@
 use crate::domain::PersonalInformationDetector;
-use crate::domain::models::PersonalInformation;
-use crate::infrastructure::api::ApiClient;
+use crate::domain::models::{PersonalInformation, Severity};
+use crate::infrastructure::api::{ApiClient, ResponseCache};
+use crate::infrastructure::api::chunker::{TextChunk, TextChunker};
 use crate::infrastructure::error::AppError;
+use crate::infrastructure::repositories::reconciler;
+use crate::utils::config::ChunkConfig;
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::HashSet;
 use std::error::Error as StdError;
 use serde::{Deserialize, Serialize};
 
+const DETECTOR_NAME: &str = "API Detector";
+
+// モデルが確信度を返さなかった場合の既定値
+const DEFAULT_LLM_CONFIDENCE: f32 = 0.8;
+
 #[derive(Serialize, Deserialize, Debug)]
 struct ApiPersonalInfo {
     #[serde(rename = "type")]
@@ -14,6 +24,8 @@ struct ApiPersonalInfo {
     line: u32,
     start: u32,
     end: u32,
+    #[serde(default)]
+    confidence: Option<f32>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -23,174 +35,134 @@ struct ApiResponse {
 
 pub struct ApiDetector {
     client: Box<dyn ApiClient + Send + Sync>,
+    chunker: TextChunker,
+    max_concurrency: usize,
+    // 起動時のヘルスチェックの結果（スキャン中は再確認しない）
+    available: bool,
+    cache: Option<ResponseCache>,
 }
 
 impl ApiDetector {
     pub fn new(client: Box<dyn ApiClient + Send + Sync>) -> Self {
-        ApiDetector { client }
-    }
-    
-    // APIレスポンスを解析し、PersonalInformation構造体のVecを返す
-    fn parse_api_response(&self, response: &str, original_text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
-        // まずJSONとして解析を試みる
-        match serde_json::from_str::<ApiResponse>(response) {
-            Ok(parsed) => {
-                // 正常にJSONとして解析できた場合
-                let personal_info = parsed.personal_information.into_iter()
-                    .map(|info| PersonalInformation {
-                        type_: info.type_,
-                        value: info.value,
-                        line: info.line,
-                        start: info.start,
-                        end: info.end,
-                    })
-                    .collect();
-                
-                Ok(personal_info)
-            },
-            Err(_) => {
-                // JSONとして解析できなかった場合、レスポンス内のJSONを探す
-                self.extract_json_from_text(response)
-                    .and_then(|json_str| self.parse_api_response(json_str, original_text))
-                    .or_else(|_| {
-                        // JSONとして解析できない場合は、正規表現でフォールバック
-                        self.detect_with_regex(original_text)
-                    })
-            }
+        let config = ChunkConfig::default();
+        ApiDetector {
+            client,
+            max_concurrency: config.max_concurrency,
+            chunker: TextChunker::new(config),
+            available: true,
+            cache: None,
         }
     }
     
-    // テキスト内からJSONを抽出する
-    fn extract_json_from_text<'a>(&self, text: &'a str) -> Result<&'a str, Box<dyn StdError + Send + Sync>> {
-        // JSON開始位置（{）と終了位置（}）を探す
-        if let (Some(start), Some(end)) = (text.find('{'), text.rfind('}')) {
-            if start < end {
-                return Ok(&text[start..=end]);
-            }
-        }
-        
-        Err(AppError::ApiError("No valid JSON found in response".to_string()).into())
+    /// 応答のキャッシュを使う（同じテキスト・モデル・プロンプトではAPIを呼び出さない）
+    pub fn set_cache(&mut self, cache: ResponseCache) {
+        self.cache = Some(cache);
     }
     
-    // 正規表現を使用して個人情報を検出するフォールバック実装
-    fn detect_with_regex(&self, text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
-        // ここでは簡単な実装として正規表現ベースの検出にフォールバック
-        // 実際の実装ではRegexDetectorを呼び出すか、共通のロジックを使用する
+    // キャッシュがあればその応答を使い、なければAPIを呼び出して保存する
+    async fn call_with_cache(&self, text: &str) -> Result<String, Box<dyn StdError + Send + Sync>> {
+        let Some(cache) = &self.cache else {
+            return self.client.call(text).await;
+        };
         
-        let mut personal_info = Vec::new();
-        
-        // 各行ごとに処理
-        for (line_idx, line) in text.lines().enumerate() {
-            // メールアドレス検出
-            self.detect_email(line, line_idx as u32, &mut personal_info);
-            
-            // 電話番号検出
-            self.detect_phone_number(line, line_idx as u32, &mut personal_info);
-            
-            // クレジットカード番号検出
-            self.detect_credit_card(line, line_idx as u32, &mut personal_info);
+        let key = ResponseCache::key(text, self.client.get_model(), &self.client.prompt_version());
+        if let Some(response) = cache.get(&key) {
+            return Ok(response);
         }
         
-        Ok(personal_info)
-    }
-    
-    // メールアドレス検出
-    fn detect_email(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
-        let re = regex::Regex::new(r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap();
-        
-        for mat in re.find_iter(line) {
-            results.push(PersonalInformation {
-                type_: "email".to_string(),
-                value: mat.as_str().to_string(),
-                line: line_idx + 1,
-                start: mat.start() as u32,
-                end: mat.end() as u32,
-            });
+        let response = self.client.call(text).await?;
+        // 解析できない応答は次回に再度問い合わせるため保存しない
+        if serde_json::from_str::<ApiResponse>(&response).is_ok() {
+            if let Err(e) = cache.put(&key, &response) {
+                eprintln!("Failed to write LLM response cache: {}", e);
+            }
         }
+        Ok(response)
     }
     
-    // 電話番号検出
-    fn detect_phone_number(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
-        // 日本の電話番号パターン (例: 090-1234-5678, 0312345678 など)
-        let re = regex::Regex::new(r"(0\d{1,4}-\d{1,4}-\d{4}|0\d{9,10})").unwrap();
-        
-        for mat in re.find_iter(line) {
-            results.push(PersonalInformation {
-                type_: "phone_number".to_string(),
-                value: mat.as_str().to_string(),
-                line: line_idx + 1,
-                start: mat.start() as u32,
-                end: mat.end() as u32,
-            });
-        }
+    /// APIサーバーとモデルが使えるかを確認し、結果をこの実行の間保持する
+    ///
+    /// 使えない場合は警告を1回だけ表示し、以降 `is_available` がfalseを返す。
+    pub async fn check_availability(&mut self, pull_missing: bool) -> bool {
+        self.available = match self.client.check_model(pull_missing).await {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Warning: LLM detection is disabled for this run ({}). Falling back to local detectors only.", e);
+                false
+            }
+        };
+        self.available
     }
     
-    // クレジットカード番号検出
-    fn detect_credit_card(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
-        // クレジットカード番号パターン (16桁の数字, 空白やハイフン区切りも許容)
-        let re = regex::Regex::new(r"(?:\d[ -]?){13,16}").unwrap();
-        
-        for mat in re.find_iter(line) {
-            let card_num = mat.as_str().replace([' ', '-'], "");
-            
-            // Luhnアルゴリズムによる妥当性チェック (簡易版)
-            if self.is_valid_credit_card(&card_num) {
-                results.push(PersonalInformation {
-                    type_: "credit_card".to_string(),
-                    value: mat.as_str().to_string(),
-                    line: line_idx + 1,
-                    start: mat.start() as u32,
-                    end: mat.end() as u32,
-                });
-            }
-        }
+    /// LLMに送る前のテキスト分割の設定を変更する
+    pub fn set_chunk_config(&mut self, config: ChunkConfig) {
+        self.max_concurrency = config.max_concurrency.max(1);
+        self.chunker = TextChunker::new(config);
     }
     
-    // Luhnアルゴリズムによるクレジットカード番号の妥当性チェック
-    fn is_valid_credit_card(&self, card_num: &str) -> bool {
-        let digits: Vec<u32> = card_num
-            .chars()
-            .filter_map(|c| c.to_digit(10))
-            .collect();
+    // 1チャンク分のAPI呼び出しを行い、検出結果を元の文書の位置に変換する
+    async fn detect_chunk(&self, chunk: &TextChunk) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
+        let api_response = self.call_with_cache(&chunk.text).await?;
+        let personal_info = self.parse_api_response(&api_response)?;
         
-        if digits.len() < 13 || digits.len() > 19 {
-            return false;
-        }
+        // モデルが返した位置は信頼できないため、チャンクのテキストと照合して修正する
+        let personal_info = reconciler::reconcile(personal_info, &chunk.text);
         
-        let mut sum = 0;
-        let mut double = false;
-        
-        for &digit in digits.iter().rev() {
-            let mut value = digit;
-            if double {
-                value *= 2;
-                if value > 9 {
-                    value -= 9;
-                }
-            }
-            sum += value;
-            double = !double;
-        }
+        Ok(personal_info.into_iter()
+            .map(|mut info| {
+                (info.line, info.start, info.end) = chunk.to_document_position(info.line, info.start, info.end);
+                info
+            })
+            .collect())
+    }
+    
+    // APIレスポンスを解析し、PersonalInformation構造体のVecを返す
+    // （クライアントがスキーマを検証するが、キャッシュの応答も含めて形式が違えばエラーにする）
+    fn parse_api_response(&self, response: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
+        let parsed: ApiResponse = serde_json::from_str(response)
+            .map_err(|e| AppError::SchemaError(format!("invalid detection response: {}", e)))?;
         
-        sum % 10 == 0
+        Ok(parsed.personal_information.into_iter()
+            .map(|info| PersonalInformation {
+                severity: Severity::for_type(&info.type_),
+                location: None,
+                page: None,
+                type_: info.type_,
+                value: info.value,
+                line: info.line,
+                start: info.start,
+                end: info.end,
+                confidence: info.confidence.unwrap_or(DEFAULT_LLM_CONFIDENCE).clamp(0.0, 1.0),
+                detector: DETECTOR_NAME.to_string(),
+            })
+            .collect())
     }
 }
 
 #[async_trait]
 impl PersonalInformationDetector for ApiDetector {
     async fn detect(&self, text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
-        // APIを呼び出し
-        let api_response = self.client.call(text).await?;
+        // テキストをチャンクに分割し、同時実行数を制限してAPIを呼び出す
+        let chunks = self.chunker.split(text);
+        let requests: Vec<_> = chunks.iter().map(|chunk| self.detect_chunk(chunk)).collect();
+        let chunk_results: Vec<Vec<PersonalInformation>> = stream::iter(requests)
+            .buffered(self.max_concurrency)
+            .try_collect()
+            .await?;
         
-        // APIレスポンスの解析
-        self.parse_api_response(&api_response, text)
+        // チャンクの重なり部分で重複した検出結果を除去する（位置は元の文書での位置に変換済み）
+        let mut seen = HashSet::new();
+        Ok(chunk_results.into_iter()
+            .flatten()
+            .filter(|info| seen.insert((info.type_.clone(), info.line, info.start)))
+            .collect())
     }
     
     fn name(&self) -> &str {
-        "API Detector"
+        DETECTOR_NAME
     }
     
     fn is_available(&self) -> bool {
-        true // 常に利用可能だが、実際にはAPI呼び出し時にエラーが発生する可能性がある
+        self.available
     }
 }
\ No newline at end of file
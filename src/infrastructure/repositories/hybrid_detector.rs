@@ -1,82 +1,242 @@
 use crate::domain::PersonalInformationDetector;
+use crate::domain::DetectionReport;
 use crate::domain::models::PersonalInformation;
+use crate::infrastructure::repositories::verifier::LlmVerifier;
+use crate::utils::config::{default_type_aliases, DetectorSettings, MergeStrategy};
 use async_trait::async_trait;
+use futures::future;
 use std::error::Error as StdError;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
+// 実行設定付きの検出器
+struct DetectorEntry {
+    detector: Box<dyn PersonalInformationDetector + Send + Sync>,
+    timeout: Option<Duration>,
+    required: bool,
+}
+
+/// 複数の検出器を並行に実行し、結果をマージする検出器
 pub struct HybridDetector {
-    detectors: Vec<Box<dyn PersonalInformationDetector + Send + Sync>>,
+    detectors: Vec<DetectorEntry>,
+    merge_strategy: MergeStrategy,
+    // 別名（小文字） → 正規のタイプ名
+    type_aliases: HashMap<String, String>,
+    // verifyモードでマージ後の検出結果を確認する
+    verifier: Option<LlmVerifier>,
+}
+
+// 同じ行・同じタイプで範囲が重なる検出結果のまとまり
+struct FindingCluster {
+    best: PersonalInformation,
+    start: u32,
+    end: u32,
+    detectors: HashSet<usize>,
+}
+
+impl FindingCluster {
+    fn new(detector_idx: usize, info: PersonalInformation) -> Self {
+        FindingCluster {
+            start: info.start,
+            end: info.end,
+            detectors: HashSet::from([detector_idx]),
+            best: info,
+        }
+    }
+    
+    // 範囲が重なる場合だけ同一の検出とみなす（同じ行に同じ値が複数回現れる場合は別々の検出）
+    fn matches(&self, info: &PersonalInformation) -> bool {
+        self.best.line == info.line
+            && self.best.type_ == info.type_
+            && info.start < self.end && self.start < info.end
+    }
+    
+    fn add(&mut self, detector_idx: usize, info: PersonalInformation) {
+        self.start = self.start.min(info.start);
+        self.end = self.end.max(info.end);
+        self.detectors.insert(detector_idx);
+        if info.confidence > self.best.confidence {
+            self.best = info;
+        }
+    }
 }
 
 impl HybridDetector {
     pub fn new() -> Self {
-        HybridDetector {
+        let mut detector = HybridDetector {
             detectors: Vec::new(),
-        }
+            merge_strategy: MergeStrategy::default(),
+            type_aliases: HashMap::new(),
+            verifier: None,
+        };
+        detector.set_type_aliases(&default_type_aliases());
+        detector
     }
     
+    #[allow(dead_code)]
     pub fn add_detector(&mut self, detector: Box<dyn PersonalInformationDetector + Send + Sync>) {
-        self.detectors.push(detector);
+        self.add_detector_with_settings(detector, DetectorSettings::default());
+    }
+    
+    /// タイムアウトや必須フラグを指定して検出器を追加する
+    pub fn add_detector_with_settings(&mut self, detector: Box<dyn PersonalInformationDetector + Send + Sync>, settings: DetectorSettings) {
+        self.detectors.push(DetectorEntry {
+            detector,
+            timeout: settings.timeout_ms.map(Duration::from_millis),
+            required: settings.required,
+        });
+    }
+    
+    pub fn set_merge_strategy(&mut self, strategy: MergeStrategy) {
+        self.merge_strategy = strategy;
+    }
+    
+    /// verifyモードにする（マージ後の検出結果のうちLLMが確認したものだけを報告する）
+    pub fn set_verifier(&mut self, verifier: LlmVerifier) {
+        self.verifier = Some(verifier);
+    }
+    
+    /// 検出タイプの別名表（正規のタイプ名 → 別名のリスト）を設定する
+    pub fn set_type_aliases(&mut self, aliases: &HashMap<String, Vec<String>>) {
+        self.type_aliases = aliases.iter()
+            .flat_map(|(canonical, names)| {
+                names.iter()
+                    .chain(std::iter::once(canonical))
+                    .map(move |name| (name.to_lowercase(), canonical.clone()))
+            })
+            .collect();
     }
     
-    // 重複を除去して結果をマージする
-    fn merge_results(&self, multiple_results: Vec<Vec<PersonalInformation>>) -> Vec<PersonalInformation> {
-        let mut result = Vec::new();
-        let mut seen = HashSet::new();
+    // 別名を正規のタイプ名に変換する
+    fn canonical_type(&self, type_: &str) -> String {
+        self.type_aliases.get(&type_.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| type_.to_string())
+    }
+    
+    // 範囲の重なる検出結果をまとめ、マージ方法に従って結果を選ぶ
+    // 重なった検出結果のうち確信度の最も高いものを残す
+    // 投票数は、そのタイプを報告しうる検出器（`supported_types` が `None` か、タイプを含むもの）の中で数える
+    fn merge_results(&self, multiple_results: Vec<(Option<Vec<String>>, Vec<PersonalInformation>)>) -> Vec<PersonalInformation> {
+        let mut supported: Vec<Option<HashSet<String>>> = Vec::new();
+        let mut clusters: Vec<FindingCluster> = Vec::new();
         
-        for detector_results in multiple_results {
-            for info in detector_results {
-                // 既に同じ情報が含まれていないか確認
-                let key = format!("{}:{}:{}:{}", info.type_, info.value, info.line, info.start);
-                if !seen.contains(&key) {
-                    seen.insert(key);
-                    result.push(info);
+        for (detector_idx, (types, detector_results)) in multiple_results.into_iter().enumerate() {
+            supported.push(types.map(|types| types.iter().map(|type_| self.canonical_type(type_)).collect()));
+            for mut info in detector_results {
+                info.type_ = self.canonical_type(&info.type_);
+                match clusters.iter_mut().find(|cluster| cluster.matches(&info)) {
+                    Some(cluster) => cluster.add(detector_idx, info),
+                    None => clusters.push(FindingCluster::new(detector_idx, info)),
                 }
             }
         }
         
+        let required_votes = |type_: &str| {
+            let voters = supported.iter()
+                .filter(|types| types.as_ref().is_none_or(|types| types.contains(type_)))
+                .count();
+            match self.merge_strategy {
+                MergeStrategy::Union => 1,
+                MergeStrategy::Intersection => voters,
+                MergeStrategy::Majority => voters / 2 + 1,
+            }
+        };
+        
+        let mut result: Vec<PersonalInformation> = clusters.into_iter()
+            .filter(|cluster| cluster.detectors.len() >= required_votes(&cluster.best.type_))
+            .map(|cluster| cluster.best)
+            .collect();
+        result.sort_by_key(|info| (info.line, info.start));
         result
     }
 }
 
+impl Default for HybridDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl PersonalInformationDetector for HybridDetector {
     async fn detect(&self, text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
+        Ok(self.detect_with_report(text).await?.personal_information)
+    }
+    
+    async fn detect_with_report(&self, text: &str) -> Result<DetectionReport, Box<dyn StdError + Send + Sync>> {
         if self.detectors.is_empty() {
-            return Ok(Vec::new());
+            return Ok(DetectionReport::default());
         }
         
+        // 利用可能な検出器を並行に実行（個別のタイムアウト付き）
+        let runs = self.detectors.iter()
+            .filter(|entry| entry.detector.is_available())
+            .map(|entry| async move {
+                let outcome = match entry.timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, entry.detector.detect_with_report(text))
+                        .await
+                        .unwrap_or_else(|_| Err(format!("timed out after {:.1?}", timeout).into())),
+                    None => entry.detector.detect_with_report(text).await,
+                };
+                (entry, outcome)
+            });
+        let outcomes = future::join_all(runs).await;
+        
         let mut results = Vec::new();
         let mut errors = Vec::new();
+        let mut incomplete_detectors = Vec::new();
         
-        // すべての検出器を実行
-        for detector in &self.detectors {
-            if detector.is_available() {
-                match detector.detect(text).await {
-                    Ok(detector_result) => {
-                        // 成功した場合は結果を追加
-                        println!("Detector '{}' found {} personal information items", 
-                            detector.name(), detector_result.len());
-                        results.push(detector_result);
-                    }
-                    Err(e) => {
-                        // エラーが発生した場合はログに記録
-                        println!("Detector '{}' failed: {}", detector.name(), e);
-                        errors.push(format!("{}:{}", detector.name(), e));
+        for (entry, outcome) in outcomes {
+            let name = entry.detector.name();
+            match outcome {
+                Ok(report) => {
+                    // 成功した場合は結果を追加
+                    eprintln!("Detector '{}' found {} personal information items", 
+                        name, report.personal_information.len());
+                    results.push((entry.detector.supported_types(), report.personal_information));
+                    incomplete_detectors.extend(report.incomplete_detectors);
+                }
+                Err(e) => {
+                    // 必須の検出器が失敗した場合はスキャン全体をエラーにする
+                    if entry.required {
+                        return Err(format!("Required detector '{}' failed: {}", name, e).into());
                     }
+                    // エラーが発生した場合はログに記録し、未完了として報告する
+                    eprintln!("Detector '{}' failed: {}", name, e);
+                    errors.push(format!("{}:{}", name, e));
+                    incomplete_detectors.push(name.to_string());
                 }
             }
         }
         
         // 少なくとも1つの検出器が成功していれば結果を返す
         if !results.is_empty() {
-            Ok(self.merge_results(results))
+            let mut personal_information = self.merge_results(results);
+            
+            // verifyモードではLLMが確認した検出結果だけを残す
+            // （確認に失敗した場合は未確認の候補を報告せず、未完了として次回に再スキャンする）
+            if let Some(verifier) = self.verifier.as_ref().filter(|verifier| verifier.is_available()) {
+                match verifier.verify(text, personal_information).await {
+                    Ok(verified) => personal_information = verified,
+                    Err(e) => {
+                        eprintln!("{} failed: {}", verifier.name(), e);
+                        personal_information = Vec::new();
+                        incomplete_detectors.push(verifier.name().to_string());
+                    }
+                }
+            }
+            
+            Ok(DetectionReport {
+                personal_information,
+                incomplete_detectors,
+            })
         } else if !errors.is_empty() {
             // すべての検出器が失敗した場合はエラーを返す
             Err(format!("All detectors failed: {}", errors.join(", ")).into())
         } else {
             // 利用可能な検出器がなかった場合は空の結果を返す
-            Ok(Vec::new())
+            Ok(DetectionReport::default())
         }
     }
     
@@ -86,6 +246,166 @@ impl PersonalInformationDetector for HybridDetector {
     
     fn is_available(&self) -> bool {
         // 少なくとも1つの検出器が利用可能であればtrue
-        self.detectors.iter().any(|d| d.is_available())
+        self.detectors.iter().any(|entry| entry.detector.is_available())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Severity;
+
+    fn finding(type_: &str, value: &str, start: u32, confidence: f32, detector: &str) -> PersonalInformation {
+        PersonalInformation {
+            type_: type_.to_string(),
+            value: value.to_string(),
+            line: 1,
+            start,
+            end: start + value.len() as u32,
+            confidence,
+            detector: detector.to_string(),
+            severity: Severity::Medium,
+            location: None,
+            page: None,
+        }
+    }
+
+    #[test]
+    fn test_overlapping_aliases_are_merged() {
+        let detector = HybridDetector::new();
+        let merged = detector.merge_results(vec![
+            (None, vec![finding("電話番号", "090-1234-5678", 3, 0.8, "API Detector")]),
+            (None, vec![finding("phone_number", "090-1234-5678", 4, 0.7, "Regex Detector")]),
+        ]);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].type_, "phone_number");
+        assert_eq!(merged[0].detector, "API Detector");
+    }
+
+    #[test]
+    fn test_repeated_value_on_one_line_is_kept() {
+        let detector = HybridDetector::new();
+        let merged = detector.merge_results(vec![
+            (None, vec![
+                finding("email", "a@example.com", 0, 0.7, "Regex Detector"),
+                finding("email", "a@example.com", 15, 0.7, "Regex Detector"),
+            ]),
+            (None, vec![finding("email", "a@example.com", 15, 0.8, "API Detector")]),
+        ]);
+
+        let starts: Vec<u32> = merged.iter().map(|info| info.start).collect();
+        assert_eq!(starts, vec![0, 15]);
+        assert_eq!(merged[1].detector, "API Detector");
+    }
+
+    struct SlowDetector;
+
+    #[async_trait]
+    impl PersonalInformationDetector for SlowDetector {
+        async fn detect(&self, _text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(Vec::new())
+        }
+
+        fn name(&self) -> &str {
+            "Slow Detector"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_detector_is_reported_as_incomplete() {
+        let timeout = DetectorSettings { timeout_ms: Some(50), required: false };
+        let mut detector = HybridDetector::new();
+        detector.add_detector_with_settings(Box::new(SlowDetector), timeout.clone());
+        detector.add_detector(Box::new(crate::infrastructure::RegexDetector::new()));
+
+        let report = detector.detect_with_report("test@example.com").await.unwrap();
+        assert_eq!(report.personal_information.len(), 1);
+        assert_eq!(report.incomplete_detectors, vec!["Slow Detector"]);
+
+        let mut strict = HybridDetector::new();
+        strict.add_detector_with_settings(Box::new(SlowDetector), DetectorSettings { required: true, ..timeout });
+        strict.add_detector(Box::new(crate::infrastructure::RegexDetector::new()));
+        assert!(strict.detect_with_report("test@example.com").await.is_err());
+    }
+
+    #[test]
+    fn test_majority_and_intersection_strategies() {
+        let results = || vec![
+            (None, vec![finding("email", "a@example.com", 0, 0.8, "A"), finding("name", "Jane Doe", 20, 0.6, "A")]),
+            (None, vec![finding("email", "a@example.com", 0, 0.7, "B")]),
+            (None, vec![finding("email", "a@example.com", 0, 0.7, "C"), finding("name", "Jane Doe", 20, 0.6, "C")]),
+        ];
+
+        let mut detector = HybridDetector::new();
+        detector.set_merge_strategy(MergeStrategy::Majority);
+        assert_eq!(detector.merge_results(results()).len(), 2);
+
+        detector.set_merge_strategy(MergeStrategy::Intersection);
+        let merged = detector.merge_results(results());
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].type_, "email");
+    }
+
+    #[tokio::test]
+    async fn test_strategies_vote_among_detectors_supporting_the_type() {
+        use crate::infrastructure::{AddressDetector, EntropyDetector, NameDetector, RegexDetector, SecretDetector};
+        use crate::utils::config::EntropyConfig;
+
+        // main.rs と同じ5つのローカル検出器（それぞれ報告するタイプが異なる）
+        let build = |strategy| {
+            let mut detector = HybridDetector::new();
+            detector.set_merge_strategy(strategy);
+            detector.add_detector(Box::new(RegexDetector::new()));
+            detector.add_detector(Box::<AddressDetector>::default());
+            detector.add_detector(Box::<NameDetector>::default());
+            detector.add_detector(Box::<SecretDetector>::default());
+            detector.add_detector(Box::new(EntropyDetector::new(EntropyConfig::default())));
+            detector
+        };
+        let text = "taro@example.com 090-1234-5678 マイナンバー: 123456789018";
+
+        for strategy in [MergeStrategy::Union, MergeStrategy::Majority, MergeStrategy::Intersection] {
+            let findings = build(strategy).detect(text).await.unwrap();
+            let types: Vec<&str> = findings.iter().map(|info| info.type_.as_str()).collect();
+            assert_eq!(types, vec!["email", "phone_number", "my_number"], "{:?}", strategy);
+        }
+    }
+
+    struct FailingClient;
+
+    #[async_trait]
+    impl crate::infrastructure::api::ApiClient for FailingClient {
+        async fn call(&self, _text: &str) -> Result<String, Box<dyn StdError + Send + Sync>> {
+            Err("connection refused".into())
+        }
+
+        async fn call_with_prompt(
+            &self,
+            _prompt: &crate::infrastructure::api::prompt::RenderedPrompt,
+            _schema: &serde_json::Value,
+        ) -> Result<String, Box<dyn StdError + Send + Sync>> {
+            Err("connection refused".into())
+        }
+
+        fn get_url(&self) -> &str {
+            "failing"
+        }
+
+        fn get_model(&self) -> &str {
+            "failing"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_verification_reports_no_unverified_findings() {
+        let mut detector = HybridDetector::new();
+        detector.add_detector(Box::new(crate::infrastructure::RegexDetector::new()));
+        detector.set_verifier(LlmVerifier::new(Box::new(FailingClient), Default::default()));
+
+        let report = detector.detect_with_report("test@example.com").await.unwrap();
+        assert!(report.personal_information.is_empty());
+        assert_eq!(report.incomplete_detectors, vec!["LLM Verifier"]);
+    }
+}
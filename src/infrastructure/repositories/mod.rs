@@ -1,9 +1,21 @@
 pub mod api_detector;
 pub mod regex_detector;
 pub mod hybrid_detector;
+pub mod address_detector;
+pub mod name_detector;
+pub mod secret_detector;
+pub mod entropy_detector;
 pub mod output_formatter;
+pub mod validators;
+pub mod reconciler;
+pub mod verifier;
 
 pub use api_detector::ApiDetector;
 pub use regex_detector::RegexDetector;
 pub use hybrid_detector::HybridDetector;
-pub use output_formatter::JsonOutputFormatter;
\ No newline at end of file
+pub use address_detector::AddressDetector;
+pub use name_detector::NameDetector;
+pub use secret_detector::SecretDetector;
+pub use entropy_detector::EntropyDetector;
+pub use output_formatter::JsonOutputFormatter;
+pub use verifier::LlmVerifier;
\ No newline at end of file
@@ -0,0 +1,386 @@
+use crate::domain::PersonalInformationDetector;
+use crate::domain::models::{PersonalInformation, Severity};
+use crate::infrastructure::error::AppError;
+use crate::utils::AppConfig;
+use async_trait::async_trait;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::error::Error as StdError;
+use std::fs;
+use regex::Regex;
+
+const DETECTOR_NAME: &str = "Name Detector";
+
+// 根拠ごとの確信度
+const LABEL_CONFIDENCE: f32 = 0.85;
+const FULL_NAME_CONFIDENCE: f32 = 0.8;
+const HONORIFIC_CONFIDENCE: f32 = 0.75;
+const DICTIONARY_PAIR_CONFIDENCE: f32 = 0.6;
+
+// 組み込み辞書
+const JA_SURNAMES: &str = include_str!("dictionaries/ja_surnames.txt");
+const JA_GIVEN_NAMES: &str = include_str!("dictionaries/ja_given_names.txt");
+const EN_SURNAMES: &str = include_str!("dictionaries/en_surnames.txt");
+const EN_GIVEN_NAMES: &str = include_str!("dictionaries/en_given_names.txt");
+
+// 氏名の直後に付く敬称
+const HONORIFICS: [&str; 9] = ["様", "さま", "さん", "氏", "殿", "君", "くん", "ちゃん", "先生"];
+
+// 敬称の前に置ける名の最大文字数
+const MAX_GIVEN_NAME_CHARS: usize = 4;
+
+// 氏名の直前に置かれるラベル（例: "氏名：", "Name:"）
+const LABEL_PATTERN: &str = r"(?:(?:氏名|お名前|名前|担当者|フリガナ|ふりがな)\s*[:：]?|\b(?i:name)\s*[:：])[\s　]*$";
+
+// 欧米系の氏名（敬称付きも含む）
+const WESTERN_NAME_PATTERN: &str = r"\b(?:(?P<title>Mr|Mrs|Ms|Miss|Dr|Prof)(?P<period>\.)?\s+)?(?P<name>[A-Z][a-z]+(?:[-'][A-Z][a-z]+)?(?:\s+[A-Z][a-z]+(?:[-'][A-Z][a-z]+)?){0,2})\b";
+const WESTERN_WORD_PATTERN: &str = r"[A-Z][a-z]+(?:[-'][A-Z][a-z]+)?";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Kanji,
+    Hiragana,
+    Katakana,
+    Other,
+}
+
+fn script_of(c: char) -> Script {
+    match c {
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '々' => Script::Kanji,
+        '\u{3041}'..='\u{309F}' => Script::Hiragana,
+        '\u{30A1}'..='\u{30FF}' => Script::Katakana,
+        _ => Script::Other,
+    }
+}
+
+/// 氏名検出に使う辞書
+pub struct NameDictionary {
+    ja_surnames: Vec<String>,
+    ja_given_names: Vec<String>,
+    en_surnames: HashSet<String>,
+    en_given_names: HashSet<String>,
+}
+
+impl NameDictionary {
+    /// 組み込みの辞書を読み込む
+    pub fn builtin() -> Self {
+        let mut dictionary = NameDictionary {
+            ja_surnames: Vec::new(),
+            ja_given_names: Vec::new(),
+            en_surnames: HashSet::new(),
+            en_given_names: HashSet::new(),
+        };
+        dictionary.add_surnames(JA_SURNAMES);
+        dictionary.add_surnames(EN_SURNAMES);
+        dictionary.add_given_names(JA_GIVEN_NAMES);
+        dictionary.add_given_names(EN_GIVEN_NAMES);
+        dictionary
+    }
+
+    /// 辞書テキストから姓を追加する（ASCIIのみの名前は欧米系として扱う）
+    pub fn add_surnames(&mut self, content: &str) {
+        for name in parse_dictionary(content) {
+            if name.is_ascii() {
+                self.en_surnames.insert(name.to_lowercase());
+            } else {
+                self.ja_surnames.push(name.to_string());
+            }
+        }
+    }
+
+    /// 辞書テキストから名を追加する（ASCIIのみの名前は欧米系として扱う）
+    pub fn add_given_names(&mut self, content: &str) {
+        for name in parse_dictionary(content) {
+            if name.is_ascii() {
+                self.en_given_names.insert(name.to_lowercase());
+            } else {
+                self.ja_given_names.push(name.to_string());
+            }
+        }
+    }
+}
+
+// 空白・改行区切りの辞書をパースする（`#` 以降はコメント）
+fn parse_dictionary(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .flat_map(|line| line.split_whitespace())
+}
+
+/// 辞書と文脈（敬称・ラベル）に基づく氏名検出器
+///
+/// 日本の氏名は「辞書の姓 + 辞書の名」「姓（+名）+ 敬称」「ラベル + 姓」のいずれかの場合に、
+/// 欧米系の氏名は「敬称 + 名前」「ラベル + 名前」「辞書の名 + 辞書の姓」の場合に `name` として報告する。
+pub struct NameDetector {
+    ja_surname_re: Option<Regex>,
+    ja_given_names: Vec<String>,
+    en_surnames: HashSet<String>,
+    en_given_names: HashSet<String>,
+    western_name_re: Regex,
+    western_word_re: Regex,
+    label_re: Regex,
+}
+
+impl NameDetector {
+    /// 組み込み辞書で検出器を作成する
+    pub fn new() -> Self {
+        Self::with_dictionary(NameDictionary::builtin())
+    }
+
+    /// 組み込み辞書に設定ファイルの `name_dictionaries` を追加して検出器を作成する
+    pub fn from_config(config: &AppConfig) -> Result<Self, Box<dyn StdError + Send + Sync>> {
+        let mut dictionary = NameDictionary::builtin();
+
+        for path in &config.name_dictionaries.surnames {
+            dictionary.add_surnames(&read_dictionary(path)?);
+        }
+        for path in &config.name_dictionaries.given_names {
+            dictionary.add_given_names(&read_dictionary(path)?);
+        }
+
+        Ok(Self::with_dictionary(dictionary))
+    }
+
+    pub fn with_dictionary(dictionary: NameDictionary) -> Self {
+        // 最長一致になるように長い名前から並べる（同じ長さの中では値の順にして重複を隣接させる）
+        let mut surnames = dictionary.ja_surnames;
+        surnames.sort_by_cached_key(|name| (Reverse(name.chars().count()), name.clone()));
+        surnames.dedup();
+        let ja_surname_re = if surnames.is_empty() {
+            None
+        } else {
+            let alternation = surnames.iter().map(|name| regex::escape(name)).collect::<Vec<_>>().join("|");
+            Some(Regex::new(&format!("(?:{})", alternation)).expect("surname pattern must compile"))
+        };
+
+        let mut ja_given_names = dictionary.ja_given_names;
+        ja_given_names.sort_by_cached_key(|name| (Reverse(name.chars().count()), name.clone()));
+        ja_given_names.dedup();
+
+        NameDetector {
+            ja_surname_re,
+            ja_given_names,
+            en_surnames: dictionary.en_surnames,
+            en_given_names: dictionary.en_given_names,
+            western_name_re: Regex::new(WESTERN_NAME_PATTERN).expect("western name pattern must compile"),
+            western_word_re: Regex::new(WESTERN_WORD_PATTERN).expect("western word pattern must compile"),
+            label_re: Regex::new(LABEL_PATTERN).expect("label pattern must compile"),
+        }
+    }
+
+    // 日本の氏名を検出する
+    fn detect_japanese(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
+        let Some(surname_re) = &self.ja_surname_re else {
+            return;
+        };
+
+        for mat in surname_re.find_iter(line) {
+            let script = mat.as_str().chars().next().map(script_of).unwrap_or(Script::Other);
+            let labeled = self.label_re.is_match(&line[..mat.start()]);
+
+            // 同じ文字種の語の途中に現れた姓は無視する（例: "大田中"）
+            let preceded_by_same_script = line[..mat.start()].chars().next_back()
+                .is_some_and(|c| script_of(c) == script);
+            if preceded_by_same_script && !labeled {
+                continue;
+            }
+
+            let found = if labeled {
+                // ラベル + 姓（+名）
+                let end = self.match_given_name(line, mat.end())
+                    .unwrap_or_else(|| extend_same_script(line, mat.end(), script, MAX_GIVEN_NAME_CHARS));
+                Some((end, LABEL_CONFIDENCE))
+            } else if let Some(end) = self.match_given_name(line, mat.end()) {
+                // 辞書の姓 + 辞書の名
+                Some((end, FULL_NAME_CONFIDENCE))
+            } else {
+                // 姓（+名）+ 敬称
+                self.match_honorific(line, mat.end(), script)
+                    .map(|end| (end, HONORIFIC_CONFIDENCE))
+            };
+
+            if let Some((end, confidence)) = found {
+                results.push(name_finding(line, line_idx, mat.start(), end, confidence));
+            }
+        }
+    }
+
+    // 姓の直後（空白1つまで可）に辞書の名があれば、その終了位置を返す
+    fn match_given_name(&self, line: &str, pos: usize) -> Option<usize> {
+        let pos = skip_name_separator(line, pos);
+        self.ja_given_names.iter()
+            .find(|given| line[pos..].starts_with(given.as_str()))
+            .map(|given| pos + given.len())
+    }
+
+    // 姓の後に同じ文字種の名（最大数文字）と敬称が続いていれば、敬称を除いた終了位置を返す
+    fn match_honorific(&self, line: &str, pos: usize, script: Script) -> Option<usize> {
+        let mut end = pos;
+        for taken in 0..=MAX_GIVEN_NAME_CHARS {
+            let rest = line[end..].trim_start_matches([' ', '　']);
+            if HONORIFICS.iter().any(|honorific| rest.starts_with(honorific)) {
+                return Some(end);
+            }
+            if taken == MAX_GIVEN_NAME_CHARS {
+                break;
+            }
+
+            let next_pos = skip_name_separator(line, end);
+            match line[next_pos..].chars().next() {
+                Some(c) if script_of(c) == script => end = next_pos + c.len_utf8(),
+                _ => break,
+            }
+        }
+        None
+    }
+
+    // 欧米系の氏名を検出する
+    fn detect_western(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
+        for caps in self.western_name_re.captures_iter(line) {
+            let Some(name) = caps.name("name") else {
+                continue;
+            };
+            // ピリオドのない敬称（"Ms Word" など）は、後ろに大文字で始まる語が2つ以上続く場合だけ敬称とみなす
+            let has_title = caps.name("title").is_some()
+                && (caps.name("period").is_some() || self.western_word_re.find_iter(name.as_str()).count() >= 2);
+            let labeled = self.label_re.is_match(&line[..name.start()]);
+
+            let found = if labeled {
+                Some((name.start(), name.end(), LABEL_CONFIDENCE))
+            } else if has_title {
+                Some((name.start(), name.end(), HONORIFIC_CONFIDENCE))
+            } else {
+                // 辞書の名 + 辞書の姓 の並びを探す
+                let words: Vec<_> = self.western_word_re.find_iter(name.as_str()).collect();
+                words.windows(2)
+                    .find(|pair| {
+                        self.en_given_names.contains(&pair[0].as_str().to_lowercase())
+                            && self.en_surnames.contains(&pair[1].as_str().to_lowercase())
+                    })
+                    .map(|pair| (name.start() + pair[0].start(), name.start() + pair[1].end(), DICTIONARY_PAIR_CONFIDENCE))
+            };
+
+            if let Some((start, end, confidence)) = found {
+                results.push(name_finding(line, line_idx, start, end, confidence));
+            }
+        }
+    }
+}
+
+impl Default for NameDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn name_finding(line: &str, line_idx: u32, start: usize, end: usize, confidence: f32) -> PersonalInformation {
+    PersonalInformation {
+        type_: "name".to_string(),
+        value: line[start..end].to_string(),
+        line: line_idx + 1,
+        start: start as u32,
+        end: end as u32,
+        confidence,
+        detector: DETECTOR_NAME.to_string(),
+        severity: Severity::for_type("name"),
+        location: None,
+        page: None,
+    }
+}
+
+// 辞書ファイルを読み込む
+fn read_dictionary(path: &str) -> Result<String, AppError> {
+    fs::read_to_string(path)
+        .map_err(|e| AppError::ConfigError(format!("Failed to read name dictionary '{}': {}", path, e)))
+}
+
+// 姓と名の間の空白（半角・全角1文字）を読み飛ばす
+fn skip_name_separator(line: &str, pos: usize) -> usize {
+    match line[pos..].chars().next() {
+        Some(c @ (' ' | '　')) => pos + c.len_utf8(),
+        _ => pos,
+    }
+}
+
+// 同じ文字種の文字が続く限り（最大 max_chars 文字）終了位置を延ばす
+fn extend_same_script(line: &str, pos: usize, script: Script, max_chars: usize) -> usize {
+    let start = skip_name_separator(line, pos);
+    let mut end = start;
+    for c in line[start..].chars().take(max_chars) {
+        if script_of(c) != script {
+            break;
+        }
+        end += c.len_utf8();
+    }
+    if end == start { pos } else { end }
+}
+
+#[async_trait]
+impl PersonalInformationDetector for NameDetector {
+    async fn detect(&self, text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
+        let mut personal_info = Vec::new();
+
+        for (line_idx, line) in text.lines().enumerate() {
+            self.detect_japanese(line, line_idx as u32, &mut personal_info);
+            self.detect_western(line, line_idx as u32, &mut personal_info);
+        }
+
+        Ok(personal_info)
+    }
+
+    fn name(&self) -> &str {
+        DETECTOR_NAME
+    }
+
+    fn supported_types(&self) -> Option<Vec<String>> {
+        Some(vec!["name".to_string()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn names(detector: &NameDetector, text: &str) -> Vec<String> {
+        detector.detect(text).await.unwrap().into_iter().map(|info| info.value).collect()
+    }
+
+    #[tokio::test]
+    async fn test_japanese_names_with_context() {
+        let detector = NameDetector::new();
+
+        assert_eq!(names(&detector, "担当の山田太郎です").await, vec!["山田太郎"]);
+        assert_eq!(names(&detector, "田中様にご連絡ください").await, vec!["田中"]);
+        assert_eq!(names(&detector, "佐藤 京介さんより").await, vec!["佐藤 京介"]);
+        assert_eq!(names(&detector, "氏名：ヤマダ ミドリ").await, vec!["ヤマダ ミドリ"]);
+        // 文脈のない姓だけでは報告しない
+        assert!(names(&detector, "千葉県の森林").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_western_names_and_custom_dictionary() {
+        let mut dictionary = NameDictionary::builtin();
+        dictionary.add_surnames("Kowalski # 追加の姓");
+        let detector = NameDetector::with_dictionary(dictionary);
+
+        assert_eq!(names(&detector, "Please contact Dr. Jane Doe today").await, vec!["Jane Doe"]);
+        assert_eq!(names(&detector, "Signed by John Smith").await, vec!["John Smith"]);
+        assert_eq!(names(&detector, "Thanks, Anna Kowalski").await, vec!["Anna Kowalski"]);
+        assert!(names(&detector, "Open Source Software").await.is_empty());
+        assert!(names(&detector, "Ms Word document").await.is_empty());
+        assert_eq!(names(&detector, "Ms. Smith called").await, vec!["Smith"]);
+        assert_eq!(names(&detector, "Mr Tom Baker").await, vec!["Tom Baker"]);
+        // 単語の一部の "name" はラベルとみなさない
+        assert!(names(&detector, "Filename: Report Draft").await.is_empty());
+        assert!(names(&detector, "Username: Admin User").await.is_empty());
+        assert_eq!(names(&detector, "Name: Report Draft").await, vec!["Report Draft"]);
+
+        // 隣接しない重複も取り除く
+        let mut dictionary = NameDictionary::builtin();
+        dictionary.add_given_names("太郎 一 花子 太郎");
+        let given_names = NameDetector::with_dictionary(dictionary).ja_given_names;
+        let unique: HashSet<&String> = given_names.iter().collect();
+        assert_eq!(unique.len(), given_names.len());
+    }
+}
@@ -13,6 +13,12 @@ impl JsonOutputFormatter {
     }
 }
 
+impl Default for JsonOutputFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl OutputService for JsonOutputFormatter {
     fn format_results(&self, results: &[ScanResult]) -> Result<String, Box<dyn StdError + Send + Sync>> {
         serde_json::to_string_pretty(results)
@@ -24,4 +30,31 @@ impl OutputService for JsonOutputFormatter {
         fs::write(output_path, json)
             .map_err(|e| AppError::IoError(e).into())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::PersonalInformationDetector;
+    use crate::infrastructure::{HybridDetector, RegexDetector};
+
+    #[tokio::test]
+    async fn test_output_includes_confidence_detector_and_severity() {
+        let mut detector = HybridDetector::new();
+        detector.add_detector(Box::new(RegexDetector::new()));
+        let results = vec![ScanResult {
+            file: "customers.txt".to_string(),
+            personal_information: detector.detect("マイナンバー: 123456789018").await.unwrap(),
+            incomplete_detectors: Vec::new(),
+            cached: false,
+        }];
+
+        let json = JsonOutputFormatter::new().format_results(&results).unwrap();
+        let output: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let finding = &output[0]["personal_information"][0];
+        assert_eq!(finding["type_"], "my_number");
+        assert_eq!(finding["confidence"].as_f64().map(|confidence| (confidence * 100.0).round()), Some(95.0));
+        assert_eq!(finding["detector"], "Regex Detector");
+        assert_eq!(finding["severity"], "critical");
+    }
+}
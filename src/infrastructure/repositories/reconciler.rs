@@ -0,0 +1,160 @@
+use crate::domain::models::PersonalInformation;
+
+// 空白や全角・半角の違いを無視して一致した場合の確信度の係数
+const FUZZY_MATCH_PENALTY: f32 = 0.7;
+
+// 正規化した文字と元のテキストでのバイト範囲
+struct NormalizedChar {
+    c: char,
+    start: usize,
+    end: usize,
+}
+
+/// LLMが報告した検出結果を元のテキストと照合し、位置を修正する
+///
+/// 値がテキスト中にそのまま見つかればその位置（報告された行に最も近いもの）に合わせ、
+/// 空白や全角・半角の違いを無視して見つかった場合は実際のテキストを値として確信度を下げる。
+/// どちらでも見つからない検出結果は除外する。
+pub fn reconcile(findings: Vec<PersonalInformation>, text: &str) -> Vec<PersonalInformation> {
+    let lines: Vec<&str> = text.lines().collect();
+    let line_starts = line_start_offsets(text);
+
+    findings.into_iter()
+        .filter_map(|info| {
+            if is_anchored(&info, &lines) {
+                return Some(info);
+            }
+            if let Some(anchored) = anchor_exact(&info, text, &line_starts) {
+                return Some(anchored);
+            }
+            let anchored = anchor_fuzzy(&info, &lines);
+            if anchored.is_none() {
+                eprintln!("Dropped '{}' finding not found in the source text", info.type_);
+            }
+            anchored
+        })
+        .collect()
+}
+
+// 報告された位置にそのまま値があるかどうか
+fn is_anchored(info: &PersonalInformation, lines: &[&str]) -> bool {
+    let Some(line) = (info.line as usize).checked_sub(1).and_then(|idx| lines.get(idx)) else {
+        return false;
+    };
+    line.get(info.start as usize..info.end as usize) == Some(info.value.as_str())
+}
+
+// 値の完全一致を探し、報告された行に最も近い位置に合わせる
+fn anchor_exact(info: &PersonalInformation, text: &str, line_starts: &[usize]) -> Option<PersonalInformation> {
+    if info.value.is_empty() {
+        return None;
+    }
+
+    let offset = text.match_indices(info.value.as_str())
+        .map(|(offset, _)| offset)
+        .min_by_key(|&offset| line_of(line_starts, offset).abs_diff(info.line))?;
+
+    let line = line_of(line_starts, offset);
+    let start = (offset - line_starts[line as usize - 1]) as u32;
+    Some(PersonalInformation {
+        line,
+        start,
+        end: start + info.value.len() as u32,
+        ..info.clone()
+    })
+}
+
+// 空白と全角・半角の違いを無視して値を探す
+fn anchor_fuzzy(info: &PersonalInformation, lines: &[&str]) -> Option<PersonalInformation> {
+    let needle: Vec<char> = info.value.chars().filter_map(normalize_char).collect();
+    if needle.is_empty() {
+        return None;
+    }
+
+    lines.iter()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let haystack = normalize_line(line);
+            haystack.windows(needle.len())
+                .find(|window| window.iter().map(|nc| nc.c).eq(needle.iter().copied()))
+                .map(|window| (idx, window[0].start, window[window.len() - 1].end))
+        })
+        .min_by_key(|&(idx, _, _)| (idx as u32 + 1).abs_diff(info.line))
+        .map(|(idx, start, end)| PersonalInformation {
+            value: lines[idx][start..end].to_string(),
+            line: idx as u32 + 1,
+            start: start as u32,
+            end: end as u32,
+            confidence: info.confidence * FUZZY_MATCH_PENALTY,
+            ..info.clone()
+        })
+}
+
+// 空白を取り除き、全角英数記号を半角に揃える
+fn normalize_char(c: char) -> Option<char> {
+    match c {
+        c if c.is_whitespace() => None,
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0),
+        'ー' | '‐' | '−' | '–' | '—' => Some('-'),
+        _ => Some(c),
+    }
+}
+
+fn normalize_line(line: &str) -> Vec<NormalizedChar> {
+    line.char_indices()
+        .filter_map(|(start, original)| {
+            normalize_char(original).map(|c| NormalizedChar { c, start, end: start + original.len_utf8() })
+        })
+        .collect()
+}
+
+// 各行の開始バイト位置
+fn line_start_offsets(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+        .collect()
+}
+
+// バイト位置を含む行番号（1始まり）
+fn line_of(line_starts: &[usize], offset: usize) -> u32 {
+    line_starts.partition_point(|&start| start <= offset) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Severity;
+
+    fn finding(value: &str, line: u32, start: u32) -> PersonalInformation {
+        PersonalInformation {
+            type_: "phone_number".to_string(),
+            value: value.to_string(),
+            line,
+            start,
+            end: start + value.len() as u32,
+            confidence: 0.8,
+            detector: "API Detector".to_string(),
+            severity: Severity::High,
+            location: None,
+            page: None,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_fixes_drops_and_flags() {
+        let text = "担当者一覧\n電話: 090-1234-5678\nFAX: ０３－１２３４－５６７８";
+        let results = reconcile(vec![
+            finding("090-1234-5678", 1, 4),
+            finding("03-1234-5678", 3, 0),
+            finding("080-0000-0000", 2, 0),
+        ], text);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!((results[0].line, results[0].start, results[0].end), (2, 8, 21));
+        assert_eq!(results[0].confidence, 0.8);
+
+        assert_eq!(results[1].value, "０３－１２３４－５６７８");
+        assert_eq!((results[1].line, results[1].start), (3, 5));
+        assert!(results[1].confidence < 0.8);
+    }
+}
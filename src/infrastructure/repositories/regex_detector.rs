@@ -1,95 +1,133 @@
 use crate::domain::PersonalInformationDetector;
-use crate::domain::models::PersonalInformation;
+use crate::domain::models::{PersonalInformation, Severity};
+use crate::infrastructure::error::AppError;
+use crate::infrastructure::repositories::validators;
+use crate::utils::config::{default_detection_patterns, DetectionRuleConfig, ValidatorKind};
+use crate::utils::AppConfig;
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::error::Error as StdError;
 use regex::Regex;
 
-pub struct RegexDetector;
+const DETECTOR_NAME: &str = "Regex Detector";
 
-impl RegexDetector {
-    pub fn new() -> Self {
-        RegexDetector
-    }
-    
-    // メールアドレス検出
-    fn detect_email(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
-        let re = Regex::new(r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap();
+// 確信度の既定値（検証器で妥当性を確認できたルールはより高くする）
+const DEFAULT_CONFIDENCE: f32 = 0.7;
+const VALIDATED_CONFIDENCE: f32 = 0.95;
+
+// コンパイル済みの検出ルール
+struct DetectionRule {
+    type_: String,
+    patterns: Vec<Regex>,
+    validator: Option<ValidatorKind>,
+    confidence: f32,
+    severity: Severity,
+}
+
+impl DetectionRule {
+    fn compile(type_: &str, config: &DetectionRuleConfig) -> Result<Self, AppError> {
+        let patterns = config.patterns.iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| AppError::ConfigError(format!(
+                    "Invalid pattern in detection rule '{}': {}", type_, e
+                )))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         
-        for mat in re.find_iter(line) {
-            results.push(PersonalInformation {
-                type_: "email".to_string(),
-                value: mat.as_str().to_string(),
-                line: line_idx + 1,
-                start: mat.start() as u32,
-                end: mat.end() as u32,
-            });
-        }
-    }
-    
-    // 電話番号検出
-    fn detect_phone_number(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
-        // 日本の電話番号パターン (例: 090-1234-5678, 0312345678 など)
-        let re = Regex::new(r"(0\d{1,4}-\d{1,4}-\d{4}|0\d{9,10})").unwrap();
+        let default_confidence = if config.validator.is_some() {
+            VALIDATED_CONFIDENCE
+        } else {
+            DEFAULT_CONFIDENCE
+        };
         
-        for mat in re.find_iter(line) {
-            results.push(PersonalInformation {
-                type_: "phone_number".to_string(),
-                value: mat.as_str().to_string(),
-                line: line_idx + 1,
-                start: mat.start() as u32,
-                end: mat.end() as u32,
-            });
-        }
+        Ok(DetectionRule {
+            type_: type_.to_string(),
+            patterns,
+            validator: config.validator,
+            confidence: config.confidence.unwrap_or(default_confidence),
+            severity: config.severity.unwrap_or_else(|| Severity::for_type(type_)),
+        })
     }
     
-    // クレジットカード番号検出
-    fn detect_credit_card(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
-        // クレジットカード番号パターン (16桁の数字, 空白やハイフン区切りも許容)
-        let re = Regex::new(r"(?:\d[ -]?){13,16}").unwrap();
-        
-        for mat in re.find_iter(line) {
-            let card_num = mat.as_str().replace([' ', '-'], "");
-            
-            // Luhnアルゴリズムによる妥当性チェック
-            if self.is_valid_credit_card(&card_num) {
+    // 1行分のテキストにルールを適用する
+    // パターンに名前付きグループ `value` がある場合はその部分だけを検出値とする
+    // 次の検索は検出値の直後から始める（`value` の後ろの境界の文字を次の検出値の前の境界として使えるように）
+    fn apply(&self, line: &str, line_idx: u32, results: &mut Vec<PersonalInformation>) {
+        for re in &self.patterns {
+            let mut search_from = 0;
+            while let Some(caps) = re.captures_at(line, search_from) {
+                let Some(mat) = caps.name("value").or_else(|| caps.get(0)) else {
+                    break;
+                };
+                search_from = if mat.end() > search_from {
+                    mat.end()
+                } else {
+                    // 空の検出値で止まらないように1文字進める
+                    match line[search_from..].chars().next() {
+                        Some(c) => search_from + c.len_utf8(),
+                        None => break,
+                    }
+                };
+                if let Some(validator) = self.validator {
+                    if !validators::validate(validator, mat.as_str()) {
+                        continue;
+                    }
+                }
+                
                 results.push(PersonalInformation {
-                    type_: "credit_card".to_string(),
+                    type_: self.type_.clone(),
                     value: mat.as_str().to_string(),
                     line: line_idx + 1,
                     start: mat.start() as u32,
                     end: mat.end() as u32,
+                    confidence: self.confidence,
+                    detector: DETECTOR_NAME.to_string(),
+                    severity: self.severity,
+                    location: None,
+                    page: None,
                 });
             }
         }
     }
+}
+
+/// 設定の検出ルールに基づく正規表現検出器
+pub struct RegexDetector {
+    rules: Vec<DetectionRule>,
+}
+
+impl RegexDetector {
+    /// 組み込みの検出ルールで検出器を作成する
+    pub fn new() -> Self {
+        Self::from_patterns(&default_detection_patterns())
+            .expect("built-in detection patterns must compile")
+    }
     
-    // Luhnアルゴリズムによるクレジットカード番号の妥当性チェック
-    fn is_valid_credit_card(&self, card_num: &str) -> bool {
-        let digits: Vec<u32> = card_num
-            .chars()
-            .filter_map(|c| c.to_digit(10))
-            .collect();
-        
-        if digits.len() < 13 || digits.len() > 19 {
-            return false;
-        }
-        
-        let mut sum = 0;
-        let mut double = false;
+    /// 設定ファイルの `detection_patterns` から検出器を作成する
+    pub fn from_config(config: &AppConfig) -> Result<Self, Box<dyn StdError + Send + Sync>> {
+        Self::from_patterns(&config.detection_patterns)
+    }
+    
+    pub fn from_patterns(patterns: &HashMap<String, DetectionRuleConfig>) -> Result<Self, Box<dyn StdError + Send + Sync>> {
+        // 出力順を安定させるためにルール名でソートする
+        let mut names: Vec<&String> = patterns.keys().collect();
+        names.sort();
         
-        for &digit in digits.iter().rev() {
-            let mut value = digit;
-            if double {
-                value *= 2;
-                if value > 9 {
-                    value -= 9;
-                }
+        let mut rules = Vec::new();
+        for name in names {
+            let config = &patterns[name];
+            if config.enabled {
+                rules.push(DetectionRule::compile(name, config)?);
             }
-            sum += value;
-            double = !double;
         }
         
-        sum % 10 == 0
+        Ok(RegexDetector { rules })
+    }
+}
+
+impl Default for RegexDetector {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -98,26 +136,108 @@ impl PersonalInformationDetector for RegexDetector {
     async fn detect(&self, text: &str) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
         let mut personal_info = Vec::new();
         
-        // 各行ごとに処理
+        // 各行ごとにすべてのルールを適用
         for (line_idx, line) in text.lines().enumerate() {
-            // メールアドレス検出
-            self.detect_email(line, line_idx as u32, &mut personal_info);
-            
-            // 電話番号検出
-            self.detect_phone_number(line, line_idx as u32, &mut personal_info);
+            let mut line_info = Vec::new();
+            let mut validated_spans = Vec::new();
+            for rule in &self.rules {
+                let first = line_info.len();
+                rule.apply(line, line_idx as u32, &mut line_info);
+                if rule.validator.is_some() {
+                    validated_spans.extend(line_info[first..].iter().map(|info| (info.start, info.end)));
+                }
+            }
             
-            // クレジットカード番号検出
-            self.detect_credit_card(line, line_idx as u32, &mut personal_info);
+            // 検証を通過したより長い検出値の一部（例: 空白区切りのカード番号の先頭12桁のマイナンバー）は報告しない
+            personal_info.extend(line_info.into_iter().filter(|info| {
+                !validated_spans.iter().any(|&(start, end)| {
+                    start <= info.start && info.end <= end && end - start > info.end - info.start
+                })
+            }));
         }
         
         Ok(personal_info)
     }
     
     fn name(&self) -> &str {
-        "Regex Detector"
+        DETECTOR_NAME
+    }
+    
+    fn supported_types(&self) -> Option<Vec<String>> {
+        Some(self.rules.iter().map(|rule| rule.type_.clone()).collect())
     }
     
     fn is_available(&self) -> bool {
         true // 常に利用可能
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_custom_rule_and_disabled_rule() {
+        let mut patterns = default_detection_patterns();
+        patterns.insert(
+            "employee_id".to_string(),
+            DetectionRuleConfig::new(vec![r"EMP-\d{6}".to_string()]),
+        );
+        patterns.get_mut("email").unwrap().enabled = false;
+        
+        let detector = RegexDetector::from_patterns(&patterns).unwrap();
+        let results = detector.detect("社員番号: EMP-012345 / test@example.com").await.unwrap();
+        
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].type_, "employee_id");
+        assert_eq!(results[0].value, "EMP-012345");
+    }
+
+    #[tokio::test]
+    async fn test_my_number_requires_check_digit() {
+        let detector = RegexDetector::new();
+        let results = detector.detect("個人番号：1234 5678 9018\n番号：123456789012").await.unwrap();
+        
+        let my_numbers: Vec<_> = results.iter().filter(|info| info.type_ == "my_number").collect();
+        assert_eq!(my_numbers.len(), 1);
+        assert_eq!(my_numbers[0].value, "1234 5678 9018");
+        assert_eq!(my_numbers[0].line, 1);
+    }
+
+    #[tokio::test]
+    async fn test_spaced_card_number_is_not_a_my_number() {
+        let detector = RegexDetector::new();
+        for text in ["カード: 4111 1111 1111 1111", "カード: ４１１１　１１１１　１１１１　１１１１"] {
+            let results = detector.detect(text).await.unwrap();
+            let types: Vec<&str> = results.iter().map(|info| info.type_.as_str()).collect();
+            assert_eq!(types, vec!["credit_card"], "{}", text);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_adjacent_numbers_share_the_separator() {
+        let detector = RegexDetector::new();
+        let results = detector.detect("123456789018 123456789018,123456789018\n7000012050002 7000012050002").await.unwrap();
+
+        let types: Vec<(&str, u32, u32)> = results.iter()
+            .filter(|info| info.type_ == "my_number" || info.type_ == "corporate_number")
+            .map(|info| (info.type_.as_str(), info.line, info.start))
+            .collect();
+        assert_eq!(types, vec![
+            ("my_number", 1, 0),
+            ("my_number", 1, 13),
+            ("my_number", 1, 26),
+            ("corporate_number", 2, 0),
+            ("corporate_number", 2, 14),
+        ]);
+    }
+
+    #[test]
+    fn test_invalid_pattern_names_rule() {
+        let mut patterns = HashMap::new();
+        patterns.insert("broken".to_string(), DetectionRuleConfig::new(vec!["(".to_string()]));
+        
+        let err = RegexDetector::from_patterns(&patterns).err().unwrap();
+        assert!(err.to_string().contains("'broken'"));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::utils::config::ValidatorKind;
+
+/// 検出値が指定された検証器のチェックを通過するかどうかを返す
+pub fn validate(kind: ValidatorKind, value: &str) -> bool {
+    let digits = to_digits(value);
+    match kind {
+        ValidatorKind::Luhn => is_valid_luhn(&digits),
+        ValidatorKind::MyNumber => is_valid_my_number(&digits),
+        ValidatorKind::CorporateNumber => is_valid_corporate_number(&digits),
+    }
+}
+
+// 半角・全角の数字を取り出す（区切り文字などそれ以外の文字は無視する）
+fn to_digits(value: &str) -> Vec<u32> {
+    value
+        .chars()
+        .filter_map(|c| match c {
+            '０'..='９' => Some(c as u32 - '０' as u32),
+            _ => c.to_digit(10),
+        })
+        .collect()
+}
+
+// Luhnアルゴリズムによる妥当性チェック
+fn is_valid_luhn(digits: &[u32]) -> bool {
+    if digits.len() < 13 || digits.len() > 19 {
+        return false;
+    }
+    
+    let mut sum = 0;
+    let mut double = false;
+    
+    for &digit in digits.iter().rev() {
+        let mut value = digit;
+        if double {
+            value *= 2;
+            if value > 9 {
+                value -= 9;
+            }
+        }
+        sum += value;
+        double = !double;
+    }
+    
+    sum % 10 == 0
+}
+
+// マイナンバーのチェックデジット検証
+// 末尾を除く11桁を右から n = 1..11 として、重み Qn = n + 1 (n <= 6), n - 5 (n >= 7)
+fn is_valid_my_number(digits: &[u32]) -> bool {
+    if digits.len() != 12 {
+        return false;
+    }
+    
+    let (body, check) = digits.split_at(11);
+    let sum: u32 = body.iter().rev()
+        .enumerate()
+        .map(|(i, &p)| {
+            let n = i as u32 + 1;
+            let q = if n <= 6 { n + 1 } else { n - 5 };
+            p * q
+        })
+        .sum();
+    
+    let remainder = sum % 11;
+    let expected = if remainder <= 1 { 0 } else { 11 - remainder };
+    check[0] == expected
+}
+
+// 法人番号のチェックデジット検証
+// 先頭がチェックデジットで、残り12桁を右から n = 1..12 として、重み Qn = 1 (奇数), 2 (偶数)
+fn is_valid_corporate_number(digits: &[u32]) -> bool {
+    if digits.len() != 13 {
+        return false;
+    }
+    
+    let (check, body) = digits.split_at(1);
+    let sum: u32 = body.iter().rev()
+        .enumerate()
+        .map(|(i, &p)| if i % 2 == 0 { p } else { p * 2 })
+        .sum();
+    
+    check[0] == 9 - sum % 9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_my_number_check_digit() {
+        assert!(validate(ValidatorKind::MyNumber, "123456789018"));
+        assert!(validate(ValidatorKind::MyNumber, "1234-5678-9018"));
+        assert!(validate(ValidatorKind::MyNumber, "１２３４ ５６７８ ９０１８"));
+        assert!(!validate(ValidatorKind::MyNumber, "123456789012"));
+    }
+
+    #[test]
+    fn test_corporate_number_check_digit() {
+        assert!(validate(ValidatorKind::CorporateNumber, "7000012050002"));
+        assert!(!validate(ValidatorKind::CorporateNumber, "1000012050002"));
+    }
+}
@@ -0,0 +1,248 @@
+use crate::domain::models::PersonalInformation;
+use crate::infrastructure::api::ApiClient;
+use crate::infrastructure::api::prompt::{create_verification_prompt, Language};
+use crate::infrastructure::api::schema;
+use crate::infrastructure::error::AppError;
+use crate::utils::config::VerificationConfig;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+
+const VERIFIER_NAME: &str = "LLM Verifier";
+
+// LLMに送る検出候補
+#[derive(Serialize)]
+struct VerificationCandidate<'a> {
+    id: usize,
+    #[serde(rename = "type")]
+    type_: &'a str,
+    value: &'a str,
+    context: String,
+}
+
+#[derive(Deserialize)]
+struct VerificationResponse {
+    verdicts: Vec<Verdict>,
+}
+
+#[derive(Deserialize)]
+struct Verdict {
+    id: usize,
+    keep: bool,
+    #[serde(default)]
+    reason: String,
+}
+
+/// ローカルの検出器が見つけた候補をLLMで確認し、個人情報と判定されたものだけを残す
+///
+/// 各候補は前後の短い文脈とともにバッチで送るため、送信量は文書の長さではなく候補数に比例する。
+pub struct LlmVerifier {
+    client: Box<dyn ApiClient + Send + Sync>,
+    config: VerificationConfig,
+    // 起動時のヘルスチェックの結果（スキャン中は再確認しない）
+    available: bool,
+}
+
+impl LlmVerifier {
+    pub fn new(client: Box<dyn ApiClient + Send + Sync>, config: VerificationConfig) -> Self {
+        LlmVerifier {
+            client,
+            config,
+            available: true,
+        }
+    }
+
+    /// APIサーバーとモデルが使えるかを確認し、結果をこの実行の間保持する
+    pub async fn check_availability(&mut self, pull_missing: bool) -> bool {
+        self.available = match self.client.check_model(pull_missing).await {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Warning: LLM verification is disabled for this run ({}). Reporting unverified findings.", e);
+                false
+            }
+        };
+        self.available
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.available
+    }
+
+    pub fn name(&self) -> &str {
+        VERIFIER_NAME
+    }
+
+    /// 検出結果を確認し、LLMが個人情報と判定したものだけを元の順序で返す
+    pub async fn verify(&self, text: &str, findings: Vec<PersonalInformation>) -> Result<Vec<PersonalInformation>, Box<dyn StdError + Send + Sync>> {
+        if findings.is_empty() {
+            return Ok(findings);
+        }
+
+        let line_starts = line_start_offsets(text);
+        let batch_size = self.config.batch_size.max(1);
+        let requests: Vec<_> = findings.chunks(batch_size)
+            .map(|batch| self.verify_batch(text, &line_starts, batch))
+            .collect();
+        let keep_flags: Vec<Vec<bool>> = stream::iter(requests)
+            .buffered(self.config.max_concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        Ok(findings.into_iter()
+            .zip(keep_flags.into_iter().flatten())
+            .filter_map(|(info, keep)| keep.then_some(info))
+            .collect())
+    }
+
+    // 1バッチ分の候補を確認し、候補ごとに残すかどうかを返す（判定のない候補は残さない）
+    async fn verify_batch(&self, text: &str, line_starts: &[usize], batch: &[PersonalInformation]) -> Result<Vec<bool>, Box<dyn StdError + Send + Sync>> {
+        let candidates: Vec<VerificationCandidate> = batch.iter()
+            .enumerate()
+            .map(|(id, info)| VerificationCandidate {
+                id,
+                type_: &info.type_,
+                value: &info.value,
+                context: context_window(text, line_starts, info, self.config.context_chars),
+            })
+            .collect();
+
+        let contexts: String = candidates.iter().map(|candidate| candidate.context.as_str()).collect();
+        let prompt = create_verification_prompt(&serde_json::to_string(&candidates)?, Language::detect(&contexts));
+        let response = self.client.call_with_prompt(&prompt, &schema::verification_schema()).await?;
+        let parsed: VerificationResponse = serde_json::from_str(&response)
+            .map_err(|e| AppError::SchemaError(format!("invalid verification response: {}", e)))?;
+
+        let verdicts: HashMap<usize, Verdict> = parsed.verdicts.into_iter()
+            .map(|verdict| (verdict.id, verdict))
+            .collect();
+
+        Ok(batch.iter()
+            .enumerate()
+            .map(|(id, info)| match verdicts.get(&id) {
+                Some(verdict) if verdict.keep => true,
+                Some(verdict) => {
+                    eprintln!("Dropped '{}' finding on line {}: {}", info.type_, info.line, verdict.reason);
+                    false
+                }
+                None => {
+                    eprintln!("Dropped '{}' finding on line {}: no verdict returned", info.type_, info.line);
+                    false
+                }
+            })
+            .collect())
+    }
+}
+
+// 検出値の前後 `context_chars` 文字を含む文脈を作り、値を [[ ]] で囲む
+fn context_window(text: &str, line_starts: &[usize], info: &PersonalInformation, context_chars: usize) -> String {
+    let Some(start) = line_starts.get((info.line as usize).saturating_sub(1))
+        .map(|line_start| line_start + info.start as usize)
+        .filter(|&start| text.is_char_boundary(start))
+    else {
+        return format!("[[{}]]", info.value);
+    };
+    let end = start + info.value.len();
+    if text.get(start..end) != Some(info.value.as_str()) {
+        return format!("[[{}]]", info.value);
+    }
+
+    let before_start = text[..start].char_indices()
+        .rev()
+        .nth(context_chars.saturating_sub(1))
+        .map_or(0, |(pos, _)| pos);
+    let after_end = text[end..].char_indices()
+        .nth(context_chars)
+        .map_or(text.len(), |(pos, _)| end + pos);
+
+    format!("{}[[{}]]{}", &text[before_start..start], info.value, &text[end..after_end])
+}
+
+// 各行の開始バイト位置
+fn line_start_offsets(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Severity;
+    use crate::infrastructure::api::prompt::RenderedPrompt;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    // 送られた候補を記録し、注文番号だけを誤検出と判定するクライアント
+    struct MockClient {
+        bodies: std::sync::Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl ApiClient for MockClient {
+        async fn call(&self, _text: &str) -> Result<String, Box<dyn StdError + Send + Sync>> {
+            unreachable!()
+        }
+
+        async fn call_with_prompt(&self, prompt: &RenderedPrompt, _schema: &serde_json::Value) -> Result<String, Box<dyn StdError + Send + Sync>> {
+            self.bodies.lock().unwrap().push(prompt.body.clone());
+            let candidates: Vec<serde_json::Value> = serde_json::from_str(&prompt.body)?;
+            let verdicts: Vec<serde_json::Value> = candidates.iter()
+                .map(|candidate| serde_json::json!({
+                    "id": candidate["id"],
+                    "keep": !candidate["context"].as_str().unwrap().contains("注文番号: [["),
+                    "reason": "context",
+                }))
+                .collect();
+            Ok(serde_json::json!({ "verdicts": verdicts }).to_string())
+        }
+
+        fn get_url(&self) -> &str {
+            "mock"
+        }
+
+        fn get_model(&self) -> &str {
+            "mock"
+        }
+    }
+
+    fn finding(value: &str, line: u32, start: u32) -> PersonalInformation {
+        PersonalInformation {
+            type_: "phone_number".to_string(),
+            value: value.to_string(),
+            line,
+            start,
+            end: start + value.len() as u32,
+            confidence: 0.7,
+            detector: "Regex Detector".to_string(),
+            severity: Severity::High,
+            location: None,
+            page: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_keeps_confirmed_findings_in_batches() {
+        let filler = "本文".repeat(500);
+        let text = format!("{}\n電話: 090-1234-5678\n注文番号: 0312345678\n{}", filler, filler);
+        let bodies = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let verifier = LlmVerifier::new(
+            Box::new(MockClient { bodies: bodies.clone() }),
+            VerificationConfig { enabled: true, context_chars: 10, batch_size: 1, max_concurrency: 2 },
+        );
+
+        let results = verifier.verify(&text, vec![
+            finding("090-1234-5678", 2, 8),
+            finding("0312345678", 3, 14),
+        ]).await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].value, "090-1234-5678");
+
+        // 1候補ずつ送られ、文書全体ではなく前後の文脈だけが含まれる
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies[0].contains("電話: [[090-1234-5678]]\\n注文番号"));
+        assert!(bodies.iter().all(|body| body.len() < 300));
+    }
+}
@@ -1,11 +1,14 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand};
 use std::path::Path;
 use std::error::Error as StdError;
 use std::sync::Arc;
 
 use crate::domain::{ScanService, DetectionService, OutputService};
 use crate::application::{ScanUseCase, FormatUseCase};
-use crate::utils::AppConfig;
+use crate::infrastructure::api::ResponseCache;
+use crate::infrastructure::filesystem::scanner::WalkOptions;
+use crate::utils::{AppConfig, Provider};
+use crate::utils::config::CacheConfig;
 
 #[derive(Parser)]
 #[command(name = "personal-info-scanner")]
@@ -24,11 +27,21 @@ impl Cli {
         &self.command
     }
     
-    // API URLを取得
-    pub fn get_api_url(&self) -> String {
+    // LLM APIの種類を取得
+    pub fn get_provider(&self) -> Option<Provider> {
+        match &self.command {
+            Commands::Scan { provider, .. } => *provider,
+            Commands::ScanFile { provider, .. } => *provider,
+            Commands::Cache { .. } => None,
+        }
+    }
+    
+    // API URLを取得（未指定の場合はNone）
+    pub fn get_api_url(&self) -> Option<String> {
         match &self.command {
             Commands::Scan { api_url, .. } => api_url.clone(),
             Commands::ScanFile { api_url, .. } => api_url.clone(),
+            Commands::Cache { .. } => None,
         }
     }
     
@@ -37,6 +50,7 @@ impl Cli {
         match &self.command {
             Commands::Scan { model, .. } => model.clone(),
             Commands::ScanFile { model, .. } => model.clone(),
+            Commands::Cache { .. } => String::new(),
         }
     }
     
@@ -45,6 +59,7 @@ impl Cli {
         match &self.command {
             Commands::Scan { timeout, .. } => *timeout,
             Commands::ScanFile { timeout, .. } => *timeout,
+            Commands::Cache { .. } => 0,
         }
     }
     
@@ -53,6 +68,63 @@ impl Cli {
         match &self.command {
             Commands::Scan { no_api, .. } => *no_api,
             Commands::ScanFile { no_api, .. } => *no_api,
+            Commands::Cache { .. } => true,
+        }
+    }
+    
+    // 未導入のモデルを取得するかどうかを取得
+    pub fn is_pull_model(&self) -> bool {
+        match &self.command {
+            Commands::Scan { pull_model, .. } => *pull_model,
+            Commands::ScanFile { pull_model, .. } => *pull_model,
+            Commands::Cache { .. } => false,
+        }
+    }
+    
+    // verifyモード（検出候補だけをLLMで確認する）かどうかを取得
+    pub fn is_verify(&self) -> bool {
+        match &self.command {
+            Commands::Scan { verify, .. } => *verify,
+            Commands::ScanFile { verify, .. } => *verify,
+            Commands::Cache { .. } => false,
+        }
+    }
+    
+    // 応答キャッシュを使わない設定かどうかを取得
+    pub fn is_no_cache(&self) -> bool {
+        match &self.command {
+            Commands::Scan { no_cache, .. } => *no_cache,
+            Commands::ScanFile { no_cache, .. } => *no_cache,
+            Commands::Cache { .. } => true,
+        }
+    }
+    
+    // PDFファイルをスキャンするかどうかを取得
+    pub fn is_pdf_enabled(&self) -> bool {
+        match &self.command {
+            Commands::Scan { pdf, .. } => *pdf,
+            Commands::ScanFile { .. } | Commands::Cache { .. } => true,
+        }
+    }
+    
+    // DOCXファイルをスキャンするかどうかを取得
+    pub fn is_docx_enabled(&self) -> bool {
+        match &self.command {
+            Commands::Scan { docx, .. } => *docx,
+            Commands::ScanFile { .. } | Commands::Cache { .. } => true,
+        }
+    }
+    
+    // ディレクトリ走査の絞り込みを取得
+    pub fn get_walk_options(&self) -> WalkOptions {
+        match &self.command {
+            Commands::Scan { include, exclude, max_depth, hidden, .. } => WalkOptions {
+                include: include.clone(),
+                exclude: exclude.clone(),
+                max_depth: *max_depth,
+                hidden: *hidden,
+            },
+            Commands::ScanFile { .. } | Commands::Cache { .. } => WalkOptions::default(),
         }
     }
     
@@ -61,6 +133,7 @@ impl Cli {
         match &self.command {
             Commands::Scan { config, .. } => config.clone(),
             Commands::ScanFile { config, .. } => config.clone(),
+            Commands::Cache { action: CacheAction::Clear { config } } => config.clone(),
         }
     }
 }
@@ -78,16 +151,20 @@ pub enum Commands {
         output: Option<String>,
         
         /// PDFファイルをスキャンするかどうか
-        #[arg(short, long, default_value = "true")]
+        #[arg(short, long, default_value_t = true, action = ArgAction::Set)]
         pdf: bool,
         
         /// DOCXファイルをスキャンするかどうか
-        #[arg(long, default_value = "true")]
+        #[arg(long, default_value_t = true, action = ArgAction::Set)]
         docx: bool,
         
-        /// Ollama APIのエンドポイント
-        #[arg(long, default_value = "http://localhost:11434/api/generate")]
-        api_url: String,
+        /// LLM APIの種類（ollama または openai）
+        #[arg(long, value_enum)]
+        provider: Option<Provider>,
+        
+        /// LLM APIのエンドポイント（省略時は設定ファイルまたはプロバイダーごとの既定値）
+        #[arg(long)]
+        api_url: Option<String>,
         
         /// 使用するモデル
         #[arg(long, default_value = "deepseek-coder")]
@@ -97,10 +174,22 @@ pub enum Commands {
         #[arg(long, default_value = "60")]
         timeout: u64,
         
+        /// モデルがOllamaに導入されていない場合に取得する
+        #[arg(long)]
+        pull_model: bool,
+        
         /// API呼び出しをスキップし、正規表現のみで検出する
         #[arg(long)]
         no_api: bool,
         
+        /// 文書全体をLLMに送らず、ローカルの検出器の候補だけをLLMで確認する
+        #[arg(long)]
+        verify: bool,
+        
+        /// LLMの応答キャッシュを使わない
+        #[arg(long)]
+        no_cache: bool,
+        
         /// 詳細なログを出力する
         #[arg(short, long)]
         verbose: bool,
@@ -109,6 +198,26 @@ pub enum Commands {
         #[arg(short, long, default_value = "true")]
         recursive: bool,
         
+        /// スキャン対象に含めるファイルのglob（複数指定可、指定時は一致するファイルのみ）
+        #[arg(long)]
+        include: Vec<String>,
+        
+        /// スキャン対象から除外するファイル・ディレクトリのglob（複数指定可）
+        #[arg(long)]
+        exclude: Vec<String>,
+        
+        /// ディレクトリを走査する深さの上限
+        #[arg(long)]
+        max_depth: Option<usize>,
+        
+        /// 隠しファイル・隠しディレクトリもスキャンする
+        #[arg(long)]
+        hidden: bool,
+        
+        /// 差分スキャンの状態ファイル（前回から変更のないファイルは前回の結果を再利用する）
+        #[arg(long)]
+        state: Option<String>,
+        
         /// 設定ファイルのパス
         #[arg(long)]
         config: Option<String>,
@@ -127,13 +236,25 @@ pub enum Commands {
         #[arg(long)]
         no_api: bool,
         
+        /// 文書全体をLLMに送らず、ローカルの検出器の候補だけをLLMで確認する
+        #[arg(long)]
+        verify: bool,
+        
+        /// LLMの応答キャッシュを使わない
+        #[arg(long)]
+        no_cache: bool,
+        
         /// 詳細なログを出力する
         #[arg(short, long)]
         verbose: bool,
         
-        /// Ollama APIのエンドポイント
-        #[arg(long, default_value = "http://localhost:11434/api/generate")]
-        api_url: String,
+        /// LLM APIの種類（ollama または openai）
+        #[arg(long, value_enum)]
+        provider: Option<Provider>,
+        
+        /// LLM APIのエンドポイント（省略時は設定ファイルまたはプロバイダーごとの既定値）
+        #[arg(long)]
+        api_url: Option<String>,
         
         /// 使用するモデル
         #[arg(long, default_value = "deepseek-coder")]
@@ -143,6 +264,26 @@ pub enum Commands {
         #[arg(long, default_value = "60")]
         timeout: u64,
         
+        /// モデルがOllamaに導入されていない場合に取得する
+        #[arg(long)]
+        pull_model: bool,
+        
+        /// 設定ファイルのパス
+        #[arg(long)]
+        config: Option<String>,
+    },
+    
+    /// LLMの応答キャッシュを操作します
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// キャッシュをすべて削除します
+    Clear {
         /// 設定ファイルのパス
         #[arg(long)]
         config: Option<String>,
@@ -153,6 +294,7 @@ pub enum Commands {
 pub struct CliRunner {
     scan_use_case: ScanUseCase,
     format_use_case: FormatUseCase,
+    cache_config: CacheConfig,
 }
 
 impl CliRunner {
@@ -169,9 +311,15 @@ impl CliRunner {
                 Some(config.max_concurrency),
             ),
             format_use_case: FormatUseCase::new(output_service),
+            cache_config: config.cache.clone(),
         }
     }
     
+    /// 差分スキャンの状態ファイルに記録する設定の指紋を設定する
+    pub fn set_settings_fingerprint(&mut self, fingerprint: String) {
+        self.scan_use_case.set_settings_fingerprint(fingerprint);
+    }
+    
     pub async fn run(&self, cli: Cli) -> Result<(), Box<dyn StdError + Send + Sync>> {
         match &cli.command {
             Commands::Scan { 
@@ -179,9 +327,10 @@ impl CliRunner {
                 output, 
                 recursive,
                 verbose,
+                state,
                 ..
             } => {
-                self.run_scan(path, output.as_deref(), *recursive, *verbose).await
+                self.run_scan(path, output.as_deref(), *recursive, state.as_deref(), *verbose).await
             },
             Commands::ScanFile { 
                 file_path, 
@@ -191,10 +340,20 @@ impl CliRunner {
             } => {
                 self.run_scan_file(file_path, output.as_deref(), *verbose).await
             },
+            Commands::Cache { action: CacheAction::Clear { .. } } => {
+                self.run_cache_clear()
+            },
         }
     }
     
-    async fn run_scan(&self, path: &str, output: Option<&str>, recursive: bool, verbose: bool) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    fn run_cache_clear(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let cache = ResponseCache::open(&self.cache_config)?;
+        let removed = cache.clear()?;
+        println!("キャッシュを削除しました: {}件 ({})", removed, cache.dir().display());
+        Ok(())
+    }
+    
+    async fn run_scan(&self, path: &str, output: Option<&str>, recursive: bool, state: Option<&str>, verbose: bool) -> Result<(), Box<dyn StdError + Send + Sync>> {
         if verbose {
             println!("スキャン開始: {}", path);
         }
@@ -206,11 +365,12 @@ impl CliRunner {
         }
         
         // スキャン実行
-        let results = self.scan_use_case.scan_directory(path, recursive).await?;
+        let results = self.scan_use_case.scan_directory(path, recursive, state).await?;
         
         if verbose {
-            println!("スキャン完了: {}個のファイルをスキャン, {}個の個人情報を検出", 
+            println!("スキャン完了: {}個のファイルをスキャン（うち{}個は前回の結果を再利用）, {}個の個人情報を検出", 
                 results.len(),
+                results.iter().filter(|r| r.cached).count(),
                 results.iter().map(|r| r.personal_information.len()).sum::<usize>());
         }
         
@@ -243,14 +403,14 @@ impl CliRunner {
         }
         
         // ファイルスキャン
-        let scan_result = self.scan_use_case.scan_file(file_path).await?;
+        let results = self.scan_use_case.scan_file(file_path).await?;
         
         if verbose {
-            println!("スキャン完了: {}個の個人情報を検出", scan_result.personal_information.len());
+            println!("スキャン完了: {}個の個人情報を検出",
+                results.iter().map(|r| r.personal_information.len()).sum::<usize>());
         }
         
         // 結果の出力
-        let results = vec![scan_result];
         if let Some(output_path) = output {
             self.format_use_case.write_to_file(&results, output_path)?;
             if verbose {
@@ -18,11 +18,11 @@ pub use application::{
 };
 
 pub use infrastructure::{
-    OllamaClient,
-    PdfExtractor, DocxExtractor, PlainTextExtractor, ExtractorManager,
+    OllamaClient, OpenAiCompatibleClient,
+    PdfExtractor, DocxExtractor, SpreadsheetExtractor, PresentationExtractor, PlainTextExtractor, ExtractorManager,
     FileSystemScanner,
-    ApiDetector, RegexDetector, HybridDetector,
-    JsonOutputFormatter
+    ApiDetector, RegexDetector, HybridDetector, AddressDetector, NameDetector, SecretDetector, EntropyDetector,
+    JsonOutputFormatter, LlmVerifier
 };
 
 pub use interfaces::{
@@ -70,7 +70,7 @@ mod tests {
         );
         
         // スキャン実行
-        let results = scan_use_case.scan_directory(dir.path().to_str().unwrap(), true).await.unwrap();
+        let results = scan_use_case.scan_directory(dir.path().to_str().unwrap(), true, None).await.unwrap();
         
         // 検証
         assert_eq!(results.len(), 1);
@@ -80,4 +80,71 @@ mod tests {
         let email_info = personal_info.iter().find(|info| info.type_ == "email").unwrap();
         assert_eq!(email_info.value, "test@example.com");
     }
+
+    #[tokio::test]
+    async fn test_incremental_scan_reuses_unchanged_files() {
+        let dir = tempdir().unwrap();
+        let state_path = dir.path().join("state.json");
+        let scan_dir = dir.path().join("docs");
+        std::fs::create_dir(&scan_dir).unwrap();
+        std::fs::write(scan_dir.join("a.txt"), "mail: a@example.com").unwrap();
+        std::fs::write(scan_dir.join("b.txt"), "mail: b@example.com").unwrap();
+
+        let mut extractor_manager = ExtractorManager::new();
+        extractor_manager.register(PlainTextExtractor);
+        let scan_use_case = ScanUseCase::new(
+            Arc::new(FileSystemScanner::new(extractor_manager)),
+            Arc::new(DetectionServiceImpl::new(Arc::new(RegexDetector::new()))),
+            Some(1),
+        );
+        let scan_path = scan_dir.to_str().unwrap();
+        let state = Some(state_path.to_str().unwrap());
+
+        let first = scan_use_case.scan_directory(scan_path, true, state).await.unwrap();
+        assert!(first.iter().all(|result| !result.cached));
+
+        // 内容とサイズを変えたファイルだけが再スキャンされる
+        std::fs::write(scan_dir.join("b.txt"), "mail: changed@example.com").unwrap();
+        let second = scan_use_case.scan_directory(scan_path, true, state).await.unwrap();
+        assert_eq!(second.len(), 2);
+
+        let a = second.iter().find(|result| result.file.ends_with("a.txt")).unwrap();
+        let b = second.iter().find(|result| result.file.ends_with("b.txt")).unwrap();
+        assert!(a.cached);
+        assert_eq!(a.personal_information[0].value, "a@example.com");
+        assert!(!b.cached);
+        assert_eq!(b.personal_information[0].value, "changed@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_incremental_scan_skips_state_file_and_drops_state_on_settings_change() {
+        let dir = tempdir().unwrap();
+        let state_path = dir.path().join("state.json");
+        std::fs::write(dir.path().join("a.txt"), "mail: a@example.com").unwrap();
+
+        let scan_use_case = |settings: &str| {
+            let mut extractor_manager = ExtractorManager::new();
+            extractor_manager.register(PlainTextExtractor);
+            let mut scan_use_case = ScanUseCase::new(
+                Arc::new(FileSystemScanner::new(extractor_manager)),
+                Arc::new(DetectionServiceImpl::new(Arc::new(RegexDetector::new()))),
+                Some(1),
+            );
+            scan_use_case.set_settings_fingerprint(settings.to_string());
+            scan_use_case
+        };
+        let scan_path = dir.path().to_str().unwrap();
+        let state = Some(state_path.to_str().unwrap());
+
+        // スキャン対象の中にある状態ファイルはスキャンしない
+        scan_use_case("v1").scan_directory(scan_path, true, state).await.unwrap();
+        let second = scan_use_case("v1").scan_directory(scan_path, true, state).await.unwrap();
+        assert_eq!(second.len(), 1);
+        assert!(second[0].cached);
+
+        // 設定が変わった場合は前回の結果を再利用しない
+        let third = scan_use_case("v2").scan_directory(scan_path, true, state).await.unwrap();
+        assert_eq!(third.len(), 1);
+        assert!(!third[0].cached);
+    }
 }
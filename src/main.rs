@@ -9,12 +9,14 @@ mod utils;
 
 use interfaces::Cli;
 use infrastructure::{
-    OllamaClient, ApiDetector, RegexDetector, HybridDetector, 
-    JsonOutputFormatter, FileSystemScanner, PdfExtractor, 
-    DocxExtractor, PlainTextExtractor, ExtractorManager
+    OllamaClient, OpenAiCompatibleClient, ApiDetector, RegexDetector, HybridDetector, AddressDetector, NameDetector, SecretDetector, EntropyDetector,
+    JsonOutputFormatter, LlmVerifier, FileSystemScanner, PdfExtractor, 
+    DocxExtractor, SpreadsheetExtractor, PresentationExtractor, PlainTextExtractor, ExtractorManager
 };
+use infrastructure::api::ResponseCache;
+use infrastructure::api::prompt::PromptBuilder;
 use application::DetectionServiceImpl;
-use utils::AppConfig;
+use utils::{AppConfig, Provider};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -30,36 +32,106 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // 抽出器マネージャーの設定
     let mut extractor_manager = ExtractorManager::new();
     extractor_manager.register(PlainTextExtractor);
-    extractor_manager.register(PdfExtractor);
-    extractor_manager.register(DocxExtractor);
+    if cli.is_pdf_enabled() {
+        extractor_manager.register(PdfExtractor);
+    }
+    if cli.is_docx_enabled() {
+        extractor_manager.register(DocxExtractor);
+    }
+    extractor_manager.register(SpreadsheetExtractor);
+    extractor_manager.register(PresentationExtractor);
+    extractor_manager.set_enabled_file_types(&config.supported_file_types);
     
     // ファイルシステムスキャナーの作成
-    let scan_service = Arc::new(FileSystemScanner::new(extractor_manager));
+    let mut scanner = FileSystemScanner::new(extractor_manager);
+    scanner.set_walk_options(cli.get_walk_options());
+    scanner.set_archive_config(config.archive.clone());
+    let scan_service = Arc::new(scanner);
     
     // 検出器の選択と作成
-    let detector: Arc<dyn domain::PersonalInformationDetector + Send + Sync> = if cli.is_no_api() {
-        // 正規表現のみの検出
-        Arc::new(RegexDetector::new())
-    } else {
-        // APIクライアントの作成
-        let api_client = Box::new(OllamaClient::new(
-            &cli.get_api_url(),
-            &cli.get_model(),
-            Some(cli.get_timeout() * 1000),
-        ));
-        
-        // ハイブリッド検出器の作成と設定
-        let mut hybrid_detector = HybridDetector::new();
-        
-        // APIベースの検出器を追加
-        hybrid_detector.add_detector(Box::new(ApiDetector::new(api_client)));
-        
-        // フォールバック用に正規表現検出器も追加
-        hybrid_detector.add_detector(Box::new(RegexDetector::new()));
-        
-        Arc::new(hybrid_detector)
+    let mut hybrid_detector = HybridDetector::new();
+    hybrid_detector.set_merge_strategy(config.merge_strategy);
+    hybrid_detector.set_type_aliases(&config.type_aliases);
+    
+    // 設定ファイルの検出器ごとの設定（タイムアウト・必須）を適用して追加する
+    let mut detector_names = Vec::new();
+    let mut add_detector = |detector: Box<dyn domain::PersonalInformationDetector + Send + Sync>| {
+        let settings = config.detectors.get(detector.name()).cloned().unwrap_or_default();
+        detector_names.push(detector.name().to_string());
+        hybrid_detector.add_detector_with_settings(detector, settings);
     };
     
+    let mut verifier = None;
+    let mut llm_settings = String::from("no-api");
+    if !cli.is_no_api() {
+        // APIクライアントの作成（プロバイダーを変更した場合のURLはそのプロバイダーの既定値）
+        let provider = cli.get_provider().unwrap_or(config.provider);
+        let api_url = cli.get_api_url().unwrap_or_else(|| {
+            if provider == config.provider {
+                config.api_url.clone()
+            } else {
+                provider.default_api_url().to_string()
+            }
+        });
+        let timeout_ms = Some(cli.get_timeout() * 1000);
+        let prompt_builder = PromptBuilder::from_prompt_config(&config.prompt)?;
+        let verify = cli.is_verify() || config.verification.enabled;
+        llm_settings = format!(
+            "provider={:?} url={} model={} prompt={} verify={}",
+            provider, api_url, cli.get_model(), prompt_builder.version(), verify
+        );
+        let api_client: Box<dyn infrastructure::api::ApiClient + Send + Sync> = match provider {
+            Provider::Ollama => {
+                let mut client = OllamaClient::new(&api_url, &cli.get_model(), timeout_ms);
+                client.set_prompt_builder(prompt_builder);
+                Box::new(client)
+            }
+            Provider::OpenAi => {
+                let mut client = OpenAiCompatibleClient::new(
+                    &api_url,
+                    &cli.get_model(),
+                    config.resolve_api_key(),
+                    timeout_ms,
+                );
+                client.set_prompt_builder(prompt_builder);
+                Box::new(client)
+            }
+        };
+        
+        if verify {
+            // verifyモード: ローカルの検出器の候補だけをLLMで確認する
+            let mut llm_verifier = LlmVerifier::new(api_client, config.verification.clone());
+            llm_verifier.check_availability(cli.is_pull_model()).await;
+            verifier = Some(llm_verifier);
+        } else {
+            // APIベースの検出器を追加（長い文書はチャンクに分割して送る）
+            let mut api_detector = ApiDetector::new(api_client);
+            api_detector.set_chunk_config(config.chunking.clone());
+            if config.cache.enabled && !cli.is_no_cache() {
+                match ResponseCache::open(&config.cache) {
+                    Ok(cache) => api_detector.set_cache(cache),
+                    Err(e) => eprintln!("Warning: LLM response cache is disabled ({})", e),
+                }
+            }
+            // サーバーとモデルを起動時に1回だけ確認し、使えなければ以降のAPI呼び出しを行わない
+            api_detector.check_availability(cli.is_pull_model()).await;
+            add_detector(Box::new(api_detector));
+        }
+    }
+    
+    // ローカルの検出器（API未使用時やフォールバック用）
+    add_detector(Box::new(RegexDetector::from_config(&config)?));
+    add_detector(Box::<AddressDetector>::default());
+    add_detector(Box::new(NameDetector::from_config(&config)?));
+    add_detector(Box::<SecretDetector>::default());
+    add_detector(Box::new(EntropyDetector::new(config.entropy.clone())));
+    
+    if let Some(verifier) = verifier {
+        hybrid_detector.set_verifier(verifier);
+    }
+    
+    let detector: Arc<dyn domain::PersonalInformationDetector + Send + Sync> = Arc::new(hybrid_detector);
+    
     // 検出サービスの作成
     let detection_service = Arc::new(DetectionServiceImpl::new(detector));
     
@@ -67,13 +139,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let output_service = Box::new(JsonOutputFormatter::new());
     
     // CLIランナーの作成と実行
-    let runner = interfaces::CliRunner::new(
+    let mut runner = interfaces::CliRunner::new(
         scan_service,
         detection_service,
         output_service,
         &config,
     );
     
+    // 差分スキャンで前回の結果を再利用してよいかを判定するための、検出結果に影響する設定
+    runner.set_settings_fingerprint(application::state::settings_fingerprint(&[
+        serde_json::to_value(&config)?.to_string(),
+        format!("pdf={} docx={}", cli.is_pdf_enabled(), cli.is_docx_enabled()),
+        llm_settings,
+        detector_names.join(","),
+    ]));
+    
     // コマンドの実行
     runner.run(cli).await
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use crate::domain::Severity;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::error::Error as StdError;
 use std::path::Path;
 use std::fs;
@@ -7,48 +8,473 @@ use std::collections::HashMap;
 /// アプリケーション設定
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
+    /// LLM APIの種類
+    #[serde(default)]
+    pub provider: Provider,
     pub api_url: String,
     pub model_name: String,
     pub timeout_ms: u64,
+    /// OpenAI互換APIに送るBearerトークン（未設定の場合は環境変数 `SEC_SCAN_API_KEY` を使う）
+    #[serde(default)]
+    pub api_key: Option<String>,
     pub max_concurrency: usize,
+    /// スキャン対象の拡張子（登録済みの抽出器が対応するもののうち、ここに含まれるものだけをスキャンする）
     pub supported_file_types: Vec<String>,
-    pub detection_patterns: HashMap<String, Vec<String>>,
+    /// 組み込みのルール名（credit_card など）で検証器を省略した場合は、その名前の既定の検証器を使う
+    #[serde(deserialize_with = "deserialize_detection_patterns")]
+    pub detection_patterns: HashMap<String, DetectionRuleConfig>,
+    #[serde(default)]
+    pub name_dictionaries: NameDictionaryConfig,
+    #[serde(default)]
+    pub entropy: EntropyConfig,
+    #[serde(default)]
+    pub merge_strategy: MergeStrategy,
+    /// 正規の検出タイプ名 → 別名のリスト（検出器間で異なるタイプ名を同一視する）
+    #[serde(default = "default_type_aliases")]
+    pub type_aliases: HashMap<String, Vec<String>>,
+    /// 検出器名（例: "API Detector"）ごとの実行設定
+    #[serde(default)]
+    pub detectors: HashMap<String, DetectorSettings>,
+    #[serde(default)]
+    pub chunking: ChunkConfig,
+    #[serde(default)]
+    pub prompt: PromptConfig,
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+}
+
+/// アーカイブ（zip / tar / tar.gz）の中のファイルをスキャンする設定
+///
+/// 展開はメモリ上で行い、アーカイブ爆弾を避けるため展開量に上限を設ける。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    pub enabled: bool,
+    /// 入れ子のアーカイブをたどる深さの上限（スキャンしたアーカイブ自体が1）
+    pub max_depth: usize,
+    /// 1つのアーカイブ（入れ子を含む）から取り出すファイル数の上限
+    pub max_entries: usize,
+    /// 1ファイルの展開後のサイズの上限（MB）
+    pub max_entry_size_mb: u64,
+    /// 1つのアーカイブ（入れ子を含む）の展開後の合計サイズの上限（MB）
+    pub max_total_size_mb: u64,
+    /// 圧縮率（展開後のサイズ / 圧縮後のサイズ）の上限
+    pub max_compression_ratio: u64,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        ArchiveConfig {
+            enabled: true,
+            max_depth: 3,
+            max_entries: 10000,
+            max_entry_size_mb: 100,
+            max_total_size_mb: 1024,
+            max_compression_ratio: 100,
+        }
+    }
+}
+
+/// LLMの応答をディスクに保存するキャッシュの設定
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    /// キャッシュディレクトリ（未指定の場合は `~/.cache/sec-scan`）
+    pub dir: Option<String>,
+    /// 保存する最大件数
+    pub max_entries: usize,
+    /// 保存する合計サイズの上限（MB）
+    pub max_size_mb: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            enabled: true,
+            dir: None,
+            max_entries: 10000,
+            max_size_mb: 100,
+        }
+    }
+}
+
+/// LLMで検出候補を確認するverifyモードの設定
+///
+/// 有効な場合、文書全体はLLMに送らず、ローカルの検出器の候補とその前後の文脈だけを送って確認する。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct VerificationConfig {
+    pub enabled: bool,
+    /// 候補の前後に含める文脈の文字数
+    pub context_chars: usize,
+    /// 1回のAPI呼び出しで確認する候補数
+    pub batch_size: usize,
+    /// 同時にAPIへ送るバッチ数
+    pub max_concurrency: usize,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        VerificationConfig {
+            enabled: false,
+            context_chars: 40,
+            batch_size: 20,
+            max_concurrency: 2,
+        }
+    }
+}
+
+/// LLMに送るプロンプトの設定
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PromptConfig {
+    /// 言語コード（"ja" または "en"）→ テンプレートファイルのパス
+    ///
+    /// テンプレートでは `{text}`（対象テキスト）、`{types}`（検出する種類）、`{examples}`（例）が置換される。
+    pub templates: HashMap<String, String>,
+    /// `{types}` に列挙する検出対象の種類
+    pub target_types: Vec<String>,
+    /// `{examples}` に埋め込むfew-shot例
+    pub examples: Vec<PromptExample>,
+}
+
+impl Default for PromptConfig {
+    fn default() -> Self {
+        PromptConfig {
+            templates: HashMap::new(),
+            target_types: [
+                "name", "email", "phone_number", "address", "postal_code",
+                "credit_card", "my_number", "date_of_birth", "bank_account",
+            ].iter().map(|type_| type_.to_string()).collect(),
+            examples: Vec::new(),
+        }
+    }
+}
+
+/// プロンプトに含める入力と期待する出力の例
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptExample {
+    pub text: String,
+    pub output: serde_json::Value,
+}
+
+/// 使用するLLM APIの種類
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    /// Ollamaの `/api/generate`
+    #[default]
+    Ollama,
+    /// OpenAI互換の `/v1/chat/completions`（llama.cpp server, vLLM, LM Studioなど）
+    #[serde(rename = "openai")]
+    #[value(name = "openai")]
+    OpenAi,
+}
+
+impl Provider {
+    /// API URLが指定されていない場合に使うエンドポイント
+    pub fn default_api_url(&self) -> &'static str {
+        match self {
+            Provider::Ollama => "http://localhost:11434/api/generate",
+            Provider::OpenAi => "http://localhost:8080/v1/chat/completions",
+        }
+    }
+}
+
+/// LLMに送る前にテキストを分割する設定
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ChunkConfig {
+    /// 1チャンクあたりの最大行数
+    pub max_lines: usize,
+    /// 1チャンクあたりの推定トークン数の上限
+    pub max_tokens: usize,
+    /// 前のチャンクと重ねる行数
+    pub overlap_lines: usize,
+    /// 同時にAPIへ送るチャンク数
+    pub max_concurrency: usize,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        ChunkConfig {
+            max_lines: 100,
+            max_tokens: 1500,
+            overlap_lines: 3,
+            max_concurrency: 2,
+        }
+    }
+}
+
+/// HybridDetector内で各検出器を実行する際の設定
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DetectorSettings {
+    /// この時間内に終わらなければ結果を待たずに打ち切る
+    pub timeout_ms: Option<u64>,
+    /// trueの場合、この検出器が失敗・タイムアウトするとスキャン全体をエラーにする
+    pub required: bool,
+}
+
+/// 複数の検出器の結果をまとめる方法
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// いずれかの検出器が検出したものをすべて報告する
+    #[default]
+    Union,
+    /// そのタイプを報告しうる成功した検出器のすべてが検出したものだけを報告する
+    Intersection,
+    /// そのタイプを報告しうる成功した検出器の過半数が検出したものだけを報告する
+    Majority,
+}
+
+/// 高エントロピー文字列検出の設定
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct EntropyConfig {
+    /// Base64系文字列のシャノンエントロピーのしきい値（ビット/文字）
+    pub base64_threshold: f64,
+    /// 16進文字列のシャノンエントロピーのしきい値（ビット/文字）
+    pub hex_threshold: f64,
+    /// 検出対象とする最小文字数
+    pub min_length: usize,
+    /// `password=` などの代入文脈の直後にある場合にエントロピーへ加算する値
+    pub context_bonus: f64,
+}
+
+impl Default for EntropyConfig {
+    fn default() -> Self {
+        EntropyConfig {
+            base64_threshold: 4.0,
+            hex_threshold: 3.0,
+            min_length: 20,
+            context_bonus: 1.0,
+        }
+    }
+}
+
+/// 氏名検出で組み込み辞書に追加する辞書ファイル
+///
+/// 各ファイルは空白または改行区切りで名前を並べたテキストで、`#` 以降はコメントとして扱う。
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NameDictionaryConfig {
+    #[serde(default)]
+    pub surnames: Vec<String>,
+    #[serde(default)]
+    pub given_names: Vec<String>,
+}
+
+/// 検出値に対して追加で行う妥当性チェックの種類
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorKind {
+    /// Luhnアルゴリズム（クレジットカード番号など）
+    Luhn,
+    /// マイナンバー（個人番号）12桁のチェックデジット
+    MyNumber,
+    /// 法人番号13桁のチェックデジット
+    CorporateNumber,
+}
+
+impl ValidatorKind {
+    /// 組み込みのルール名に対応する既定の検証器
+    pub fn for_rule(name: &str) -> Option<Self> {
+        match name {
+            "credit_card" => Some(ValidatorKind::Luhn),
+            "my_number" => Some(ValidatorKind::MyNumber),
+            "corporate_number" => Some(ValidatorKind::CorporateNumber),
+            _ => None,
+        }
+    }
+}
+
+/// 検出ルールの設定
+///
+/// 設定ファイルでは正規表現のリストだけを書く簡易形式
+/// (`"email": ["..."]`) と、検証器や有効/無効・確信度・深刻度を指定する詳細形式
+/// (`"credit_card": {"patterns": ["..."], "validator": "luhn"}`) の両方を受け付ける。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(from = "DetectionRuleEntry")]
+pub struct DetectionRuleConfig {
+    pub patterns: Vec<String>,
+    pub validator: Option<ValidatorKind>,
+    pub enabled: bool,
+    /// 省略時は検証器の有無から決める
+    pub confidence: Option<f32>,
+    /// 省略時はルール名から決める
+    pub severity: Option<Severity>,
+}
+
+impl DetectionRuleConfig {
+    pub fn new(patterns: Vec<String>) -> Self {
+        DetectionRuleConfig {
+            patterns,
+            validator: None,
+            enabled: true,
+            confidence: None,
+            severity: None,
+        }
+    }
+
+    pub fn with_validator(mut self, validator: ValidatorKind) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+}
+
+// 設定ファイル上の表現（簡易形式と詳細形式）
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DetectionRuleEntry {
+    Patterns(Vec<String>),
+    Rule {
+        patterns: Vec<String>,
+        #[serde(default)]
+        validator: Option<ValidatorKind>,
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+        #[serde(default)]
+        confidence: Option<f32>,
+        #[serde(default)]
+        severity: Option<Severity>,
+    },
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl From<DetectionRuleEntry> for DetectionRuleConfig {
+    fn from(entry: DetectionRuleEntry) -> Self {
+        match entry {
+            DetectionRuleEntry::Patterns(patterns) => DetectionRuleConfig::new(patterns),
+            DetectionRuleEntry::Rule { patterns, validator, enabled, confidence, severity } => DetectionRuleConfig {
+                patterns,
+                validator,
+                enabled,
+                confidence,
+                severity,
+            },
+        }
+    }
+}
+
+// 検証器を書かない旧形式の設定（`"credit_card": ["..."]`）でも組み込みのルールの検証が外れないようにする
+fn deserialize_detection_patterns<'de, D>(deserializer: D) -> Result<HashMap<String, DetectionRuleConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut rules = HashMap::<String, DetectionRuleConfig>::deserialize(deserializer)?;
+    for (name, rule) in rules.iter_mut() {
+        if rule.validator.is_none() {
+            rule.validator = ValidatorKind::for_rule(name);
+        }
+    }
+    Ok(rules)
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         AppConfig {
-            api_url: "http://localhost:11434/api/generate".to_string(),
+            provider: Provider::default(),
+            api_url: Provider::default().default_api_url().to_string(),
             model_name: "deepseek-coder".to_string(),
             timeout_ms: 60000,
+            api_key: None,
             max_concurrency: 4,
             supported_file_types: vec![
                 "txt".to_string(),
                 "md".to_string(),
                 "csv".to_string(),
+                "json".to_string(),
+                "xml".to_string(),
+                "html".to_string(),
+                "log".to_string(),
                 "pdf".to_string(),
                 "docx".to_string(),
+                "xlsx".to_string(),
+                "xlsm".to_string(),
+                "ods".to_string(),
+                "pptx".to_string(),
+                "odp".to_string(),
             ],
-            detection_patterns: {
-                let mut patterns = HashMap::new();
-                patterns.insert(
-                    "email".to_string(),
-                    vec![r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}".to_string()],
-                );
-                patterns.insert(
-                    "phone_number".to_string(),
-                    vec![r"(0\d{1,4}-\d{1,4}-\d{4}|0\d{9,10})".to_string()],
-                );
-                patterns.insert(
-                    "credit_card".to_string(),
-                    vec![r"(?:\d[ -]?){13,16}".to_string()],
-                );
-                patterns
-            },
+            detection_patterns: default_detection_patterns(),
+            name_dictionaries: NameDictionaryConfig::default(),
+            entropy: EntropyConfig::default(),
+            merge_strategy: MergeStrategy::default(),
+            type_aliases: default_type_aliases(),
+            detectors: HashMap::new(),
+            chunking: ChunkConfig::default(),
+            prompt: PromptConfig::default(),
+            verification: VerificationConfig::default(),
+            cache: CacheConfig::default(),
+            archive: ArchiveConfig::default(),
         }
     }
 }
 
+/// 組み込みの検出ルール
+pub fn default_detection_patterns() -> HashMap<String, DetectionRuleConfig> {
+    let mut patterns = HashMap::new();
+    patterns.insert(
+        "email".to_string(),
+        DetectionRuleConfig::new(vec![r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}".to_string()]),
+    );
+    // 日本の電話番号パターン (例: 090-1234-5678, 0312345678 など)
+    patterns.insert(
+        "phone_number".to_string(),
+        DetectionRuleConfig::new(vec![r"(0\d{1,4}-\d{1,4}-\d{4}|0\d{9,10})".to_string()]),
+    );
+    // クレジットカード番号パターン (空白やハイフン区切り、全角の区切りも許容)
+    patterns.insert(
+        "credit_card".to_string(),
+        DetectionRuleConfig::new(vec![r"(?:\d[ 　\-－]?){13,16}".to_string()])
+            .with_validator(ValidatorKind::Luhn),
+    );
+    // マイナンバー (12桁, 4桁ごとの空白・ハイフン区切りや全角数字も許容)
+    patterns.insert(
+        "my_number".to_string(),
+        DetectionRuleConfig::new(vec![
+            r"(?:^|[^\d\-－])(?P<value>\d{4}[ 　\-－]?\d{4}[ 　\-－]?\d{4})(?:$|[^\d\-－])".to_string(),
+        ])
+        .with_validator(ValidatorKind::MyNumber),
+    );
+    // 法人番号 (13桁, 先頭がチェックデジット)
+    patterns.insert(
+        "corporate_number".to_string(),
+        DetectionRuleConfig::new(vec![
+            r"(?:^|[^\d\-－])(?P<value>\d{13}|\d[ 　\-－]?\d{4}[ 　\-－]?\d{4}[ 　\-－]?\d{4})(?:$|[^\d\-－])".to_string(),
+        ])
+        .with_validator(ValidatorKind::CorporateNumber),
+    );
+    patterns
+}
+
+/// 組み込みの検出タイプ別名表
+pub fn default_type_aliases() -> HashMap<String, Vec<String>> {
+    let table: [(&str, &[&str]); 7] = [
+        ("phone_number", &["phone", "tel", "telephone", "電話番号", "電話"]),
+        ("email", &["email_address", "mail", "メールアドレス", "メール"]),
+        ("credit_card", &["credit_card_number", "card_number", "クレジットカード番号", "クレジットカード"]),
+        ("address", &["住所"]),
+        ("name", &["person_name", "full_name", "氏名", "名前"]),
+        ("my_number", &["individual_number", "マイナンバー", "個人番号"]),
+        ("postal_code", &["zip_code", "postcode", "郵便番号"]),
+    ];
+    
+    table.iter()
+        .map(|(canonical, aliases)| {
+            (canonical.to_string(), aliases.iter().map(|alias| alias.to_string()).collect())
+        })
+        .collect()
+}
+
 impl AppConfig {
     /// 設定ファイルから読み込む
     pub fn from_file(path: &str) -> Result<Self, Box<dyn StdError + Send + Sync>> {
@@ -63,6 +489,13 @@ impl AppConfig {
         Ok(config)
     }
     
+    /// APIキーを取得する（設定ファイルになければ環境変数 `SEC_SCAN_API_KEY` を使う）
+    pub fn resolve_api_key(&self) -> Option<String> {
+        self.api_key.clone()
+            .or_else(|| std::env::var("SEC_SCAN_API_KEY").ok())
+            .filter(|key| !key.is_empty())
+    }
+    
     /// 設定ファイルに保存
     #[allow(dead_code)]
     pub fn save_to_file(&self, path: &str) -> Result<(), Box<dyn StdError + Send + Sync>> {
@@ -71,4 +504,25 @@ impl AppConfig {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_old_format_rules_keep_builtin_validators() {
+        let mut config = serde_json::to_value(AppConfig::default()).unwrap();
+        config["detection_patterns"] = serde_json::json!({
+            "credit_card": [r"(?:\d[ \-]?){13,16}"],
+            "my_number": { "patterns": [r"\d{12}"] },
+            "email": [r"\S+@\S+"],
+        });
+
+        let config: AppConfig = serde_json::from_value(config).unwrap();
+        let validator = |name: &str| config.detection_patterns[name].validator;
+        assert_eq!(validator("credit_card"), Some(ValidatorKind::Luhn));
+        assert_eq!(validator("my_number"), Some(ValidatorKind::MyNumber));
+        assert_eq!(validator("email"), None);
+    }
+}
@@ -73,6 +73,12 @@ impl ScanMetrics {
     }
 }
 
+impl Default for ScanMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl fmt::Display for ScanMetrics {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Scan Metrics:")?;
@@ -2,4 +2,4 @@ pub mod config;
 pub mod metrics;
 pub mod progress;
 
-pub use config::AppConfig;
+pub use config::{AppConfig, Provider};